@@ -0,0 +1,192 @@
+use std::str::FromStr;
+
+use elements::address::Payload;
+use elements::hex::ToHex;
+use elements::secp256k1_zkp::{self, XOnlyPublicKey};
+use elements::taproot::{ControlBlock, LeafVersion, TaprootBuilder, TaprootSpendInfo};
+use serde::{Deserialize, Serialize};
+use simplicity::{elements, Cmr};
+
+use crate::error::Error;
+use crate::tx::OutputScriptInfo;
+use crate::util::{self, GetInfo, Network};
+
+/// Internal key without known discrete logarithm, as suggested by BIP 341.
+///
+/// Spending is only possible via the tapleaves.
+pub const UNSPENDABLE_INTERNAL_KEY: &str =
+    "50929b74c1a04954b78b4b6035e97a5e078a5a0f28ec96d547bfee9ace803ac0";
+
+/// Return the standard unspendable internal key.
+pub fn unspendable_internal_key() -> XOnlyPublicKey {
+    XOnlyPublicKey::from_str(UNSPENDABLE_INTERNAL_KEY).expect("valid key")
+}
+
+/// Return the tapleaf script that commits to the Simplicity program with the given CMR.
+pub fn simplicity_leaf_script(cmr: Cmr) -> elements::Script {
+    elements::Script::from(cmr.to_byte_array().to_vec())
+}
+
+#[derive(Clone, PartialEq, Eq, Debug, Deserialize, Serialize)]
+#[non_exhaustive]
+pub struct SimplicityAddressInfo {
+    pub cmr: String,
+    pub internal_key: String,
+    pub output_key: String,
+    pub merkle_root: Option<String>,
+    pub script_pub_key: String,
+    pub control_block: String,
+    pub address: elements::Address,
+}
+
+/// Return the depths of a balanced tree with the given number of leaves, in DFS order.
+fn leaf_depths(n_leaves: usize, depth: u8) -> Vec<u8> {
+    if n_leaves <= 1 {
+        vec![depth]
+    } else {
+        let mut depths = leaf_depths((n_leaves + 1) / 2, depth + 1);
+        depths.extend(leaf_depths(n_leaves / 2, depth + 1));
+        depths
+    }
+}
+
+/// Build the tap tree that contains the Simplicity program with the given CMR.
+///
+/// The Simplicity leaf comes first in the tap tree, followed by the given tapscript leaves.
+/// The leaves form a balanced tree.
+pub fn simplicity_spend_info(
+    cmr: Cmr,
+    internal_key: XOnlyPublicKey,
+    extra_leaves: &[elements::Script],
+) -> Result<TaprootSpendInfo, Error> {
+    let secp = secp256k1_zkp::Secp256k1::verification_only();
+    let leaves: Vec<_> =
+        std::iter::once((simplicity_leaf_script(cmr), util::simplicity_leaf_version()))
+            .chain(
+                extra_leaves
+                    .iter()
+                    .map(|script| (script.clone(), LeafVersion::default())),
+            )
+            .collect();
+    let mut builder = TaprootBuilder::new();
+    for ((script, version), depth) in leaves
+        .into_iter()
+        .zip(leaf_depths(1 + extra_leaves.len(), 0))
+    {
+        builder = builder
+            .add_leaf_with_ver(depth, script, version)
+            .map_err(|_| Error::TapTree)?;
+    }
+    builder
+        .finalize(&secp, internal_key)
+        .map_err(|_| Error::TapTree)
+}
+
+/// Return the control block that spends the Simplicity leaf of the given tap tree.
+pub fn simplicity_control_block(
+    spend_info: &TaprootSpendInfo,
+    cmr: Cmr,
+) -> Result<ControlBlock, Error> {
+    spend_info
+        .control_block(&(simplicity_leaf_script(cmr), util::simplicity_leaf_version()))
+        .ok_or(Error::TapTree)
+}
+
+/// Create the taproot address that locks coins to the Simplicity program with the given CMR.
+///
+/// See [`simplicity_spend_info`] for the layout of the tap tree.
+pub fn simplicity_address(
+    cmr: Cmr,
+    internal_key: XOnlyPublicKey,
+    extra_leaves: &[elements::Script],
+    network: Network,
+) -> Result<SimplicityAddressInfo, Error> {
+    let spend_info = simplicity_spend_info(cmr, internal_key, extra_leaves)?;
+    let output_key = spend_info.output_key();
+    let control_block = simplicity_control_block(&spend_info, cmr)?;
+    let address = elements::Address::p2tr_tweaked(output_key, None, network.address_params());
+
+    Ok(SimplicityAddressInfo {
+        cmr: cmr.to_string(),
+        internal_key: internal_key.to_hex(),
+        output_key: output_key.into_inner().to_hex(),
+        merkle_root: spend_info.merkle_root().map(|root| root.to_hex()),
+        script_pub_key: address.script_pubkey().to_hex(),
+        control_block: control_block.serialize().to_hex(),
+        address,
+    })
+}
+
+#[derive(Clone, PartialEq, Eq, Debug, Deserialize, Serialize)]
+#[non_exhaustive]
+pub struct AddressInfo {
+    /// Network of the address, unless its parameters are unknown
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub network: Option<Network>,
+    pub script_pub_key: OutputScriptInfo,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub witness_version: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub witness_program: Option<String>,
+    pub is_confidential: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub blinding_pubkey: Option<String>,
+    pub unconfidential: elements::Address,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub simplicity: Option<SimplicityKeyInfo>,
+}
+
+/// Comparison of a taproot output key with the key of a Simplicity-only address.
+#[derive(Clone, PartialEq, Eq, Debug, Deserialize, Serialize)]
+#[non_exhaustive]
+pub struct SimplicityKeyInfo {
+    pub cmr: String,
+    pub internal_key: String,
+    pub expected_output_key: String,
+    pub matches: bool,
+}
+
+/// Describe the given address.
+///
+/// If a CMR is given and the address is P2TR, then the output key is compared with the key
+/// that commits to the CMR as the only leaf under the unspendable internal key.
+pub fn inspect_address(
+    address: &elements::Address,
+    cmr: Option<Cmr>,
+) -> Result<AddressInfo, Error> {
+    let network = Network::from_params(address.params);
+    let script_pub_key = address
+        .script_pubkey()
+        .get_info(network.unwrap_or(Network::Custom(address.params)));
+    let (witness_version, witness_program) = match &address.payload {
+        Payload::WitnessProgram { version, program } => (Some(version.to_u8()), Some(program)),
+        _ => (None, None),
+    };
+
+    let simplicity = match (cmr, witness_version, witness_program) {
+        (Some(cmr), Some(1), Some(program)) if program.len() == 32 => {
+            let internal_key = unspendable_internal_key();
+            let output_key = simplicity_spend_info(cmr, internal_key, &[])?
+                .output_key()
+                .into_inner();
+            Some(SimplicityKeyInfo {
+                cmr: cmr.to_string(),
+                internal_key: internal_key.to_hex(),
+                expected_output_key: output_key.to_hex(),
+                matches: output_key.serialize() == program.as_slice(),
+            })
+        }
+        _ => None,
+    };
+
+    Ok(AddressInfo {
+        network,
+        script_pub_key,
+        witness_version,
+        witness_program: witness_program.map(|program| program.to_hex()),
+        is_confidential: address.is_blinded(),
+        blinding_pubkey: address.blinding_pubkey.map(|key| key.to_string()),
+        unconfidential: address.to_unconfidential(),
+        simplicity,
+    })
+}
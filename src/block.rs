@@ -0,0 +1,153 @@
+use elements::dynafed;
+use elements::hex::ToHex;
+use serde::{Deserialize, Serialize};
+use simplicity::elements;
+
+use crate::tx::TransactionInfo;
+use crate::util::{GetInfo, Network};
+
+#[derive(Clone, PartialEq, Eq, Debug, Deserialize, Serialize)]
+#[non_exhaustive]
+pub struct BlockHeaderInfo {
+    pub block_hash: elements::BlockHash,
+    pub version: u32,
+    pub previous_block_hash: elements::BlockHash,
+    pub merkle_root: elements::TxMerkleNode,
+    pub time: u32,
+    pub height: u32,
+    pub dynafed: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub legacy_challenge: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub legacy_solution: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dynafed_current: Option<DynafedParamsInfo>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dynafed_proposed: Option<DynafedParamsInfo>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dynafed_witness: Option<Vec<String>>,
+}
+
+impl GetInfo<BlockHeaderInfo> for elements::BlockHeader {
+    fn get_info(&self, network: Network) -> BlockHeaderInfo {
+        let mut info = BlockHeaderInfo {
+            block_hash: self.block_hash(),
+            version: self.version,
+            previous_block_hash: self.prev_blockhash,
+            merkle_root: self.merkle_root,
+            time: self.time,
+            height: self.height,
+            dynafed: false,
+            legacy_challenge: None,
+            legacy_solution: None,
+            dynafed_current: None,
+            dynafed_proposed: None,
+            dynafed_witness: None,
+        };
+
+        match &self.ext {
+            elements::BlockExtData::Proof {
+                challenge,
+                solution,
+            } => {
+                info.legacy_challenge = Some(challenge.to_hex());
+                info.legacy_solution = Some(solution.to_hex());
+            }
+            elements::BlockExtData::Dynafed {
+                current,
+                proposed,
+                signblock_witness,
+            } => {
+                info.dynafed = true;
+                info.dynafed_current = Some(current.get_info(network));
+                info.dynafed_proposed = Some(proposed.get_info(network));
+                info.dynafed_witness = Some(signblock_witness.iter().map(|w| w.to_hex()).collect());
+            }
+        }
+
+        info
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Debug, Deserialize, Serialize)]
+#[non_exhaustive]
+pub struct DynafedParamsInfo {
+    #[serde(rename = "type")]
+    pub type_: String,
+    pub root: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub signblockscript: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub signblock_witness_limit: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub elided_root: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fedpeg_program: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fedpegscript: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub extension_space: Option<Vec<String>>,
+}
+
+impl GetInfo<DynafedParamsInfo> for dynafed::Params {
+    fn get_info(&self, _network: Network) -> DynafedParamsInfo {
+        let type_ = if self.is_null() {
+            "null"
+        } else if self.elided_root().is_some() {
+            "compact"
+        } else {
+            "full"
+        }
+        .to_owned();
+
+        DynafedParamsInfo {
+            type_,
+            root: self.calculate_root().to_string(),
+            signblockscript: self.signblockscript().map(|s| s.to_hex()),
+            signblock_witness_limit: self.signblock_witness_limit(),
+            elided_root: self.elided_root().map(|r| r.to_string()),
+            fedpeg_program: self.fedpeg_program().map(|s| s.to_hex_string()),
+            fedpegscript: self.fedpegscript().map(|s| s.to_hex()),
+            extension_space: self
+                .extension_space()
+                .map(|space| space.iter().map(|x| x.to_hex()).collect()),
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Debug, Deserialize, Serialize)]
+#[non_exhaustive]
+pub struct BlockInfo {
+    pub header: BlockHeaderInfo,
+    pub size: usize,
+    pub weight: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub txids: Option<Vec<elements::Txid>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transactions: Option<Vec<TransactionInfo>>,
+}
+
+impl GetInfo<BlockInfo> for elements::Block {
+    fn get_info(&self, network: Network) -> BlockInfo {
+        BlockInfo {
+            header: self.header.get_info(network),
+            size: self.size(),
+            weight: self.weight(),
+            txids: None,
+            transactions: Some(self.txdata.iter().map(|tx| tx.get_info(network)).collect()),
+        }
+    }
+}
+
+impl BlockInfo {
+    /// Describe the given block, listing only the ids of its transactions.
+    pub fn with_txids(block: &elements::Block, network: Network) -> Self {
+        BlockInfo {
+            header: block.header.get_info(network),
+            size: block.size(),
+            weight: block.weight(),
+            txids: Some(block.txdata.iter().map(|tx| tx.txid()).collect()),
+            transactions: None,
+        }
+    }
+}
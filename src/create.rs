@@ -0,0 +1,259 @@
+use elements::hex::FromHex;
+use elements::taproot::ControlBlock;
+use elements::{confidential, OutPoint, TxIn, TxInWitness, TxOut, TxOutWitness};
+use serde::{Deserialize, Serialize};
+use simplicity::elements;
+use simplicity::jet::{Elements, Jet};
+use simplicity::RedeemNode;
+
+use crate::error::{Error, ErrorContext};
+use crate::tx::{self, AssetIssuanceInfo, OutpointInfo};
+use crate::{decode, encode, env, witness};
+
+// The templates follow the field layout of `TransactionInfo` and friends,
+// so the output of `tx decode` can be fed back into `tx create`.
+// Fields that are computed during decoding, such as `txid` or `asm`, are ignored.
+
+/// Description of a transaction that should be created.
+#[derive(Clone, PartialEq, Eq, Debug, Deserialize, Serialize)]
+pub struct TransactionTemplate {
+    #[serde(default = "default_version")]
+    pub version: u32,
+    #[serde(default = "default_locktime")]
+    pub locktime: elements::LockTime,
+    pub inputs: Vec<InputTemplate>,
+    pub outputs: Vec<OutputTemplate>,
+}
+
+fn default_version() -> u32 {
+    2
+}
+
+fn default_locktime() -> elements::LockTime {
+    elements::LockTime::ZERO
+}
+
+#[derive(Clone, PartialEq, Eq, Debug, Deserialize, Serialize)]
+pub struct InputTemplate {
+    pub prevout: OutpointInfo,
+    /// Peg-in inputs are not supported
+    #[serde(default)]
+    pub is_pegin: bool,
+    #[serde(default)]
+    pub script_sig: InputScriptTemplate,
+    #[serde(default = "default_sequence")]
+    pub sequence: elements::Sequence,
+    /// Asset issuances are not supported
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub issuance: Option<AssetIssuanceInfo>,
+    #[serde(default)]
+    pub witness: InputWitnessTemplate,
+}
+
+fn default_sequence() -> elements::Sequence {
+    elements::Sequence::MAX
+}
+
+/// The scriptSig of an input, given as hex.
+#[derive(Clone, PartialEq, Eq, Debug, Default, Deserialize, Serialize)]
+pub struct InputScriptTemplate {
+    #[serde(default)]
+    pub hex: String,
+}
+
+/// Witness of an input.
+///
+/// Either the raw witness stack is given, or a Simplicity spend from which the stack is built.
+#[derive(Clone, PartialEq, Eq, Debug, Default, Deserialize, Serialize)]
+pub struct InputWitnessTemplate {
+    /// Hex encodings of the elements of the witness stack
+    #[serde(default)]
+    pub stack: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub simplicity: Option<SimplicitySpendTemplate>,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug, Deserialize, Serialize)]
+pub struct SimplicitySpendTemplate {
+    /// Base 64 encoding of program
+    ///
+    /// If no witness values are given, then the program must be followed by its witness data.
+    pub program: String,
+    /// Hex encodings of the witness values, one for each witness node
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub witness: Option<Vec<String>>,
+    /// Hex encoding of the control block
+    pub control_block: String,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug, Deserialize, Serialize)]
+pub struct OutputTemplate {
+    #[serde(default)]
+    pub script_pub_key: OutputScriptTemplate,
+    pub value: u64,
+    pub asset: elements::AssetId,
+    /// Fee outputs have an empty scriptPubKey
+    #[serde(default)]
+    pub is_fee: bool,
+}
+
+/// The scriptPubKey of an output, given either as hex or as unconfidential address.
+#[derive(Clone, PartialEq, Eq, Debug, Default, Deserialize, Serialize)]
+pub struct OutputScriptTemplate {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hex: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub address: Option<elements::Address>,
+}
+
+impl SimplicitySpendTemplate {
+    /// Return the taproot witness stack `[program || witness, cmr, control block]`.
+    pub fn witness_stack(&self) -> Result<Vec<Vec<u8>>, Error> {
+        let program = match &self.witness {
+            Some(values) => {
+                let program =
+                    decode::decode_program::<Elements>(&self.program).with_argument("program")?;
+                witness::finalize_with_hex(&program, values).with_argument("witness")?
+            }
+            None => decode::decode_finalized_program::<Elements>(&self.program)
+                .with_argument("program")?,
+        };
+        let control_block =
+            env::parse_control_block(&self.control_block).with_argument("control_block")?;
+        simplicity_witness_stack(&program, &control_block)
+    }
+}
+
+/// Return the taproot witness stack `[program || witness, cmr, control block]`
+/// that spends the given finalized program.
+pub fn simplicity_witness_stack<J: Jet>(
+    program: &RedeemNode<J>,
+    control_block: &ControlBlock,
+) -> Result<Vec<Vec<u8>>, Error> {
+    let program_bytes = encode::encode_bytes(|w| program.encode(w))?;
+
+    Ok(vec![
+        program_bytes,
+        program.cmr().to_byte_array().to_vec(),
+        control_block.serialize(),
+    ])
+}
+
+impl InputWitnessTemplate {
+    pub fn to_witness(&self) -> Result<TxInWitness, Error> {
+        let script_witness = match &self.simplicity {
+            Some(spend) => spend.witness_stack()?,
+            None => self
+                .stack
+                .iter()
+                .map(|hex| Vec::<u8>::from_hex(hex))
+                .collect::<Result<_, _>>()
+                .with_argument("stack")?,
+        };
+
+        Ok(TxInWitness {
+            amount_rangeproof: None,
+            inflation_keys_rangeproof: None,
+            script_witness,
+            pegin_witness: vec![],
+        })
+    }
+}
+
+impl InputTemplate {
+    pub fn to_txin(&self) -> Result<TxIn, Error> {
+        let txin = self.to_unsigned_txin()?;
+        let script_sig = Vec::<u8>::from_hex(&self.script_sig.hex).with_argument("script_sig")?;
+
+        Ok(TxIn {
+            script_sig: elements::Script::from(script_sig),
+            witness: self.witness.to_witness()?,
+            ..txin
+        })
+    }
+
+    /// Create the input without its script_sig and witness.
+    pub fn to_unsigned_txin(&self) -> Result<TxIn, Error> {
+        if self.is_pegin {
+            return Err(Error::Unsupported("peg-in inputs"));
+        }
+        if self.issuance.is_some() {
+            return Err(Error::Unsupported("asset issuances"));
+        }
+
+        Ok(TxIn {
+            previous_output: OutPoint::new(self.prevout.txid, self.prevout.vout),
+            is_pegin: false,
+            script_sig: elements::Script::new(),
+            sequence: self.sequence,
+            asset_issuance: tx::null_issuance(),
+            witness: TxInWitness::default(),
+        })
+    }
+}
+
+impl OutputTemplate {
+    pub fn to_txout(&self) -> Result<TxOut, Error> {
+        let script_pubkey = match (&self.script_pub_key.hex, &self.script_pub_key.address) {
+            _ if self.is_fee => elements::Script::new(),
+            (Some(hex), _) => {
+                elements::Script::from(Vec::<u8>::from_hex(hex).with_argument("script_pub_key")?)
+            }
+            (None, Some(address)) => {
+                if address.is_blinded() {
+                    return Err(Error::ConfidentialAddress(address.to_string()));
+                }
+                address.script_pubkey()
+            }
+            (None, None) => return Err(Error::MissingInput.with_argument("script_pub_key")),
+        };
+
+        Ok(TxOut {
+            asset: confidential::Asset::Explicit(self.asset),
+            value: confidential::Value::Explicit(self.value),
+            nonce: confidential::Nonce::Null,
+            script_pubkey,
+            witness: TxOutWitness::default(),
+        })
+    }
+}
+
+impl TransactionTemplate {
+    /// Create the transaction that is described by the template.
+    pub fn to_transaction(&self) -> Result<elements::Transaction, Error> {
+        Ok(elements::Transaction {
+            version: self.version,
+            lock_time: self.locktime,
+            input: self
+                .inputs
+                .iter()
+                .map(InputTemplate::to_txin)
+                .collect::<Result<_, _>>()?,
+            output: self
+                .outputs
+                .iter()
+                .map(OutputTemplate::to_txout)
+                .collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+    /// Create the transaction that is described by the template,
+    /// leaving the script_sig and witness of every input empty.
+    pub fn to_unsigned_transaction(&self) -> Result<elements::Transaction, Error> {
+        Ok(elements::Transaction {
+            version: self.version,
+            lock_time: self.locktime,
+            input: self
+                .inputs
+                .iter()
+                .map(InputTemplate::to_unsigned_txin)
+                .collect::<Result<_, _>>()?,
+            output: self
+                .outputs
+                .iter()
+                .map(OutputTemplate::to_txout)
+                .collect::<Result<_, _>>()?,
+        })
+    }
+}
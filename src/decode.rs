@@ -1,21 +1,84 @@
+use std::io::Cursor;
 use std::sync::Arc;
 
 use base64::engine::general_purpose;
 use base64::Engine;
+use elements::encode::Decodable;
+use simplicity::elements;
 use simplicity::jet::Jet;
-use simplicity::{BitIter, CommitNode};
+use simplicity::{BitIter, CommitNode, RedeemNode};
 
-use crate::error::Error;
-
-/// Return a bit iterator over the given base64 string.
-fn get_bit_iter(base64_string: &str) -> Result<BitIter<impl Iterator<Item = u8>>, Error> {
-    let program_bytes = general_purpose::STANDARD.decode(base64_string)?;
-    Ok(BitIter::new(program_bytes.into_iter()))
-}
+use crate::error::{Error, Offset};
 
 /// Decode a program with witness data from the given base64 string.
 pub fn decode_program<J: Jet>(base64: &str) -> Result<Arc<CommitNode<J>>, Error> {
-    let mut bits = get_bit_iter(base64)?;
-    let program = CommitNode::decode(&mut bits)?;
+    let program_bytes = general_purpose::STANDARD.decode(base64)?;
+    decode_program_bytes(&program_bytes)
+}
+
+/// Decode a program with witness data from the given bytes.
+pub fn decode_program_bytes<J: Jet>(bytes: &[u8]) -> Result<Arc<CommitNode<J>>, Error> {
+    let mut bits = BitIter::new(bytes.iter().copied());
+    let program = CommitNode::decode(&mut bits)
+        .map_err(|e| Error::from(e).at(Offset::Bit(bits.n_total_read())))?;
+    Ok(program)
+}
+
+/// Return whether the given bytes contain witness data after the encoding of the program.
+///
+/// Zero bits after the program, such as padding, do not count as witness data;
+/// any set bit does, even within the last byte of the program.
+pub fn has_witness_data<J: Jet>(bytes: &[u8]) -> Result<bool, Error> {
+    let mut bits = BitIter::new(bytes.iter().copied());
+    CommitNode::<J>::decode(&mut bits)
+        .map_err(|e| Error::from(e).at(Offset::Bit(bits.n_total_read())))?;
+    Ok(bits.any(|bit| bit))
+}
+
+/// Decode a finalized program from the given base64 string.
+///
+/// The witness data must directly follow the program in the same bitstream.
+pub fn decode_finalized_program<J: Jet>(base64: &str) -> Result<Arc<RedeemNode<J>>, Error> {
+    let program_bytes = general_purpose::STANDARD.decode(base64)?;
+    decode_finalized_program_bytes(&program_bytes)
+}
+
+/// Decode a finalized program from the given bytes.
+///
+/// The witness data must directly follow the program in the same bitstream.
+pub fn decode_finalized_program_bytes<J: Jet>(bytes: &[u8]) -> Result<Arc<RedeemNode<J>>, Error> {
+    let mut bits = BitIter::new(bytes.iter().copied());
+    let program = RedeemNode::decode(&mut bits)
+        .map_err(|e| Error::from(e).at(Offset::Bit(bits.n_total_read())))?;
     Ok(program)
 }
+
+/// Decode a consensus-encoded Elements object, such as a transaction, from the given bytes.
+///
+/// All bytes must be consumed.
+pub fn decode_elements<T: Decodable>(bytes: &[u8]) -> Result<T, Error> {
+    let mut cursor = Cursor::new(bytes);
+    let object = T::consensus_decode(&mut cursor)
+        .map_err(|e| Error::from(e).at(Offset::Byte(cursor.position() as usize)))?;
+
+    let n_consumed = cursor.position() as usize;
+    if n_consumed < bytes.len() {
+        return Err(Error::TrailingBytes(bytes.len() - n_consumed).at(Offset::Byte(n_consumed)));
+    }
+    Ok(object)
+}
+
+#[cfg(test)]
+mod tests {
+    use simplicity::jet::Elements;
+
+    use super::*;
+
+    #[test]
+    fn witness_data_after_program() {
+        // The program `unit`
+        assert!(!has_witness_data::<Elements>(&[0x24]).unwrap());
+        assert!(!has_witness_data::<Elements>(&[0x24, 0x00]).unwrap());
+        assert!(has_witness_data::<Elements>(&[0x24, 0x01]).unwrap());
+    }
+}
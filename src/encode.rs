@@ -4,13 +4,22 @@ use base64::Engine;
 use simplicity::BitWriter;
 use std::io;
 
-/// Encode a program as base64 string, using the given function over a bit writer.
-pub fn encode_base64<F>(f: F) -> Result<String, Error>
+/// Encode a program as bytes, using the given function over a bit writer.
+pub fn encode_bytes<F>(f: F) -> Result<Vec<u8>, Error>
 where
     F: Fn(&mut BitWriter<&mut Vec<u8>>) -> io::Result<usize>,
 {
     let mut program_bytes = Vec::new();
     let mut w = BitWriter::new(&mut program_bytes);
     f(&mut w).map(|_| w.flush_all())??;
+    Ok(program_bytes)
+}
+
+/// Encode a program as base64 string, using the given function over a bit writer.
+pub fn encode_base64<F>(f: F) -> Result<String, Error>
+where
+    F: Fn(&mut BitWriter<&mut Vec<u8>>) -> io::Result<usize>,
+{
+    let program_bytes = encode_bytes(f)?;
     Ok(general_purpose::STANDARD.encode(&program_bytes))
 }
@@ -0,0 +1,130 @@
+use std::rc::Rc;
+use std::str::FromStr;
+
+use elements::hex::FromHex;
+use elements::{confidential, encode};
+use serde::{Deserialize, Serialize};
+use simplicity::elements;
+use simplicity::jet::elements::{ElementsEnv, ElementsUtxo};
+use simplicity::Cmr;
+
+use crate::error::Error;
+
+/// Genesis block hash of Liquid mainnet.
+pub const LIQUID_GENESIS_HASH: &str =
+    "1466275836220db2944ca059a3a10ef6fd2ea684b0688d2c379296888a206003";
+
+/// Description of an output that is spent by a transaction.
+#[derive(Clone, PartialEq, Eq, Debug, Deserialize, Serialize)]
+pub struct UtxoInfo {
+    /// Hex encoding of the scriptPubKey
+    pub script_pub_key: String,
+    /// Explicit asset id, or hex encoding of an asset commitment
+    pub asset: String,
+    /// Explicit value in satoshi, or hex encoding of a value commitment
+    pub value: ValueInfo,
+}
+
+/// Explicit or confidential value.
+#[derive(Clone, PartialEq, Eq, Debug, Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum ValueInfo {
+    Explicit(u64),
+    Confidential(String),
+}
+
+impl UtxoInfo {
+    /// Convert the description into an output that can be used in the jet environment.
+    pub fn to_utxo(&self) -> Result<ElementsUtxo, Error> {
+        let script_pubkey = elements::Script::from(Vec::<u8>::from_hex(&self.script_pub_key)?);
+        let asset = if self.asset.len() == 64 {
+            confidential::Asset::Explicit(elements::AssetId::from_str(&self.asset)?)
+        } else {
+            encode::deserialize(&Vec::<u8>::from_hex(&self.asset)?)?
+        };
+        let value = match &self.value {
+            ValueInfo::Explicit(n) => confidential::Value::Explicit(*n),
+            ValueInfo::Confidential(hex) => encode::deserialize(&Vec::<u8>::from_hex(hex)?)?,
+        };
+
+        Ok(ElementsUtxo {
+            script_pubkey,
+            asset,
+            value,
+        })
+    }
+
+    /// Convert the description into a transaction output without nonce or proofs.
+    pub fn to_txout(&self) -> Result<elements::TxOut, Error> {
+        let utxo = self.to_utxo()?;
+        Ok(elements::TxOut {
+            asset: utxo.asset,
+            value: utxo.value,
+            nonce: confidential::Nonce::Null,
+            script_pubkey: utxo.script_pubkey,
+            witness: elements::TxOutWitness::default(),
+        })
+    }
+}
+
+/// Parse a JSON array of spent outputs.
+pub fn parse_utxos(json: &str) -> Result<Vec<ElementsUtxo>, Error> {
+    let utxos: Vec<UtxoInfo> = serde_json::from_str(json)?;
+    utxos.iter().map(UtxoInfo::to_utxo).collect()
+}
+
+/// Parse a control block from the given hex string.
+pub fn parse_control_block(hex: &str) -> Result<elements::taproot::ControlBlock, Error> {
+    let bytes = Vec::<u8>::from_hex(hex)?;
+    Ok(elements::taproot::ControlBlock::from_slice(&bytes)?)
+}
+
+/// Return the control block from the last element of the witness of the given input.
+pub fn witness_control_block(
+    tx: &elements::Transaction,
+    input_index: u32,
+) -> Result<elements::taproot::ControlBlock, Error> {
+    let input = tx
+        .input
+        .get(input_index as usize)
+        .ok_or(Error::InputIndex(input_index, tx.input.len()))?;
+    let bytes = input
+        .witness
+        .script_witness
+        .last()
+        .ok_or(Error::MissingControlBlock(input_index))?;
+    Ok(elements::taproot::ControlBlock::from_slice(bytes)?)
+}
+
+/// Build the Elements jet environment for spending the given input of the transaction.
+///
+/// If no control block is given, then it is taken from the last element of the input's witness.
+pub fn build_env(
+    tx: elements::Transaction,
+    utxos: Vec<ElementsUtxo>,
+    input_index: u32,
+    script_cmr: Cmr,
+    control_block: Option<elements::taproot::ControlBlock>,
+    genesis_hash: elements::BlockHash,
+) -> Result<ElementsEnv, Error> {
+    if input_index as usize >= tx.input.len() {
+        return Err(Error::InputIndex(input_index, tx.input.len()));
+    }
+    if utxos.len() != tx.input.len() {
+        return Err(Error::UtxoCount(utxos.len(), tx.input.len()));
+    }
+    let control_block = match control_block {
+        Some(control_block) => control_block,
+        None => witness_control_block(&tx, input_index)?,
+    };
+
+    Ok(ElementsEnv::new(
+        Rc::new(tx),
+        utxos,
+        input_index,
+        script_cmr,
+        control_block,
+        None,
+        genesis_hash,
+    ))
+}
@@ -1,28 +1,249 @@
 use std::{fmt, io};
 
+use serde::{Deserialize, Serialize};
 use simplicity::elements;
 use thiserror::Error;
 
-#[derive(Error)]
+#[derive(Error, Debug)]
 pub enum Error {
     #[error("{0}")]
     Fmt(#[from] fmt::Error),
     #[error("{0}")]
     IO(#[from] io::Error),
-    // #[error("{0}")]
-    // Hex(#[from] elements::hex::Error),
+    #[error("invalid hex: {0}")]
+    Hex(#[from] elements::hex::Error),
     #[error("{0}")]
     Encode(#[from] elements::encode::Error),
+    #[error("invalid address: {0}")]
+    Address(#[from] elements::AddressError),
     #[error("{0}")]
-    Json(#[from] serde_json::Error),
+    Pset(#[from] elements::pset::Error),
     #[error("{0}")]
+    Taproot(#[from] elements::taproot::TaprootError),
+    #[error("invalid JSON: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("invalid base64: {0}")]
     Base64(#[from] base64::DecodeError),
     #[error("{0}")]
     Simplicity(#[from] simplicity::Error),
+    #[error("failed to get randomness: {0}")]
+    Random(#[from] getrandom::Error),
+    #[error("invalid graph: {0}")]
+    Graph(String),
+    #[error("execution failed: {0}")]
+    Execution(String),
+    #[error("invalid tap tree")]
+    TapTree,
+    #[error("{0}")]
+    Secp(#[from] elements::secp256k1_zkp::Error),
+    #[error("{0}")]
+    SecpUpstream(#[from] elements::secp256k1_zkp::UpstreamError),
+    #[error("{0}")]
+    Usage(String),
+    #[error("either a program or a CMR must be given")]
+    MissingCmr,
+    #[error("no input given")]
+    MissingInput,
+    #[error("genesis block hash is only known for Liquid mainnet; pass --genesis-hash")]
+    MissingGenesisHash,
+    #[error("input is not valid UTF-8")]
+    Utf8,
+    #[error("{0} trailing bytes after end of data")]
+    TrailingBytes(usize),
+    #[error("{0} must be {1} bytes, got {2}")]
+    Length(&'static str, usize, usize),
+    #[error("encoded transaction has txid {0}, but description has txid {1}")]
+    TxidMismatch(String, String),
+    #[error("encoded transaction has wtxid {0}, but description has {1} {2}")]
+    WtxidMismatch(String, &'static str, String),
+    #[error("{0} are not supported in transaction templates")]
+    Unsupported(&'static str),
+    #[error("program has CMR {0}, but is stored under CMR {1}")]
+    CmrMismatch(String, String),
+    #[error("outputs to confidential address {0} cannot be blinded")]
+    ConfidentialAddress(String),
+    #[error("input index {0} out of range for transaction with {1} inputs")]
+    InputIndex(u32, usize),
+    #[error("got {0} spent outputs for transaction with {1} inputs")]
+    UtxoCount(usize, usize),
+    #[error("got {0} witness values for program with {1} witness nodes")]
+    WitnessCount(usize, usize),
+    #[error("witness index {0} out of range for program with {1} witness nodes")]
+    WitnessIndex(usize, usize),
+    #[error("missing value for witness node {0}")]
+    MissingWitnessValue(usize),
+    #[error("message must be 32 bytes, got {0}")]
+    MessageLength(usize),
+    #[error("{0} is not a valid witness value of type {1}")]
+    WitnessValue(String, String),
+    #[error("control block has leaf version {0:#04x} instead of the Simplicity leaf version")]
+    ControlBlockLeafVersion(u8),
+    #[error("control block does not commit to the output key of the spent output")]
+    ControlBlockCommitment,
+    #[error("witness of input {0} contains no control block")]
+    MissingControlBlock(u32),
+    #[error("input {0} carries no Simplicity program")]
+    MissingProgram(u32),
+    #[error("{offset}: {source}")]
+    Offset {
+        offset: Offset,
+        #[source]
+        source: Box<Error>,
+    },
+    #[error("argument `{argument}`: {source}")]
+    Argument {
+        argument: String,
+        #[source]
+        source: Box<Error>,
+    },
 }
 
-impl fmt::Debug for Error {
+/// Position in the input at which decoding failed.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Offset {
+    Byte(usize),
+    Bit(usize),
+}
+
+impl fmt::Display for Offset {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        fmt::Display::fmt(self, f)
+        match self {
+            Offset::Byte(n) => write!(f, "at byte {}", n),
+            Offset::Bit(n) => write!(f, "at bit {}", n),
+        }
+    }
+}
+
+/// Classes of errors, each with a distinct exit code.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ErrorClass {
+    /// Arguments are missing or inconsistent with each other
+    Usage,
+    /// Input data is malformed
+    Data,
+    /// Reading or writing files failed
+    Io,
+    /// Internal failure
+    Internal,
+}
+
+impl ErrorClass {
+    /// Return the exit code of the class, following the conventions of `sysexits.h`.
+    pub fn exit_code(self) -> i32 {
+        match self {
+            ErrorClass::Usage => 64,
+            ErrorClass::Data => 65,
+            ErrorClass::Io => 74,
+            ErrorClass::Internal => 70,
+        }
+    }
+}
+
+impl Error {
+    /// Attach the position at which decoding failed.
+    pub fn at(self, offset: Offset) -> Self {
+        Error::Offset {
+            offset,
+            source: Box::new(self),
+        }
+    }
+
+    /// Attach the name of the argument whose input failed.
+    ///
+    /// Errors that already name their argument are returned unchanged.
+    pub fn with_argument(self, argument: &str) -> Self {
+        if let Error::Argument { .. } = self {
+            return self;
+        }
+        Error::Argument {
+            argument: argument.to_owned(),
+            source: Box::new(self),
+        }
+    }
+
+    /// Return the underlying error without context.
+    pub fn root(&self) -> &Error {
+        match self {
+            Error::Offset { source, .. } | Error::Argument { source, .. } => source.root(),
+            _ => self,
+        }
+    }
+
+    /// Return the name of the argument whose input failed, if known.
+    pub fn argument(&self) -> Option<&str> {
+        match self {
+            Error::Argument { argument, .. } => Some(argument),
+            Error::Offset { source, .. } => source.argument(),
+            _ => None,
+        }
+    }
+
+    /// Return the position at which decoding failed, if known.
+    pub fn offset(&self) -> Option<Offset> {
+        match self {
+            Error::Offset { offset, .. } => Some(*offset),
+            Error::Argument { source, .. } => source.offset(),
+            _ => None,
+        }
+    }
+
+    /// Return the class of the error.
+    pub fn class(&self) -> ErrorClass {
+        match self.root() {
+            Error::IO(..) => ErrorClass::Io,
+            // Writing JSON output fails with an I/O error, for example on a closed pipe
+            Error::Json(e) if e.classify() == serde_json::error::Category::Io => ErrorClass::Io,
+            Error::Fmt(..) | Error::Random(..) | Error::Graph(..) | Error::TapTree => {
+                ErrorClass::Internal
+            }
+            Error::Usage(..)
+            | Error::MissingCmr
+            | Error::MissingInput
+            | Error::MissingGenesisHash
+            | Error::InputIndex(..)
+            | Error::UtxoCount(..)
+            | Error::WitnessCount(..)
+            | Error::WitnessIndex(..)
+            | Error::MissingWitnessValue(..)
+            | Error::MissingProgram(..) => ErrorClass::Usage,
+            _ => ErrorClass::Data,
+        }
+    }
+
+    /// Return a machine-readable description of the error.
+    pub fn to_info(&self) -> ErrorInfo {
+        ErrorInfo {
+            class: self.class(),
+            exit_code: self.class().exit_code(),
+            message: self.root().to_string(),
+            argument: self.argument().map(str::to_owned),
+            offset: self.offset(),
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Debug, Deserialize, Serialize)]
+#[non_exhaustive]
+pub struct ErrorInfo {
+    pub class: ErrorClass,
+    pub exit_code: i32,
+    pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub argument: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub offset: Option<Offset>,
+}
+
+/// Attach context to fallible results.
+pub trait ErrorContext<T> {
+    /// Attach the name of the argument whose input failed.
+    fn with_argument(self, argument: &str) -> Result<T, Error>;
+}
+
+impl<T, E: Into<Error>> ErrorContext<T> for Result<T, E> {
+    fn with_argument(self, argument: &str) -> Result<T, Error> {
+        self.map_err(|e| e.into().with_argument(argument))
     }
 }
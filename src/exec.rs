@@ -0,0 +1,77 @@
+use serde::{Deserialize, Serialize};
+use simplicity::dag::{DagLike, MaxSharing};
+use simplicity::jet::Jet;
+use simplicity::node::Inner;
+use simplicity::{BitMachine, ExecutionError, RedeemNode};
+
+#[derive(Clone, PartialEq, Eq, Debug, Deserialize, Serialize)]
+#[non_exhaustive]
+pub struct RunInfo {
+    pub success: bool,
+    pub cost: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub failed_node: Option<FailedNodeInfo>,
+}
+
+/// The `assert` or `fail` node that aborted execution.
+#[derive(Clone, PartialEq, Eq, Debug, Deserialize, Serialize)]
+#[non_exhaustive]
+pub struct FailedNodeInfo {
+    /// Index of the node in post order, as in `prog list`
+    pub index: usize,
+    pub node: String,
+    pub cmr: String,
+}
+
+/// Execute the given program on the Bit Machine in the given jet environment.
+///
+/// Execution failures are reported in the returned info,
+/// including the `assert` or `fail` node that aborted execution.
+pub fn run_program<J: Jet>(program: &RedeemNode<J>, env: &J::Environment) -> RunInfo {
+    let mut mac = BitMachine::for_program(program);
+
+    match mac.exec(program, env) {
+        Ok(output) => RunInfo {
+            success: true,
+            cost: program.bounds().cost.to_string(),
+            output: Some(output.to_string()),
+            error: None,
+            failed_node: None,
+        },
+        Err(error) => RunInfo {
+            success: false,
+            cost: program.bounds().cost.to_string(),
+            output: None,
+            failed_node: failed_node(program, &error),
+            error: Some(error.to_string()),
+        },
+    }
+}
+
+/// Find the node that caused the given execution error.
+///
+/// Assertions are found by the CMR of their pruned branch, and fail nodes by their entropy.
+/// Errors of other kinds, such as failed jets, are not attributed to a node.
+fn failed_node<J: Jet>(program: &RedeemNode<J>, error: &ExecutionError) -> Option<FailedNodeInfo> {
+    program
+        .post_order_iter::<MaxSharing<_>>()
+        .find(|item| match (error, item.node.inner()) {
+            (ExecutionError::ReachedFailNode(entropy), Inner::Fail(node_entropy)) => {
+                entropy == node_entropy
+            }
+            (ExecutionError::ReachedPrunedBranch(cmr), Inner::AssertL(_, pruned))
+            | (ExecutionError::ReachedPrunedBranch(cmr), Inner::AssertR(pruned, _)) => {
+                cmr == pruned
+            }
+            _ => false,
+        })
+        .map(|item| FailedNodeInfo {
+            index: item.index,
+            node: item.node.inner().to_string(),
+            cmr: item.node.cmr().to_string(),
+        })
+}
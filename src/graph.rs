@@ -16,17 +16,46 @@ use crate::compress;
 use crate::compress::CompressScribe;
 use crate::error::Error;
 
-pub fn visualize<J: Jet>(program: &CommitNode<J>) -> Result<(), Error> {
+/// Output formats of program graphs.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum GraphFormat {
+    /// Graphviz DOT
+    #[default]
+    Dot,
+    /// Scalable vector graphics, laid out without Graphviz
+    Svg,
+}
+
+/// Render the given program as a graph in the given format.
+///
+/// The graph is written to the given path, or printed to stdout if there is no path.
+pub fn visualize<J: Jet, P: AsRef<Path>>(
+    program: &CommitNode<J>,
+    format: GraphFormat,
+    path: Option<P>,
+) -> Result<(), Error> {
     let dot = program_to_dot(program)?;
     let dot = types_to_superscript(&dot);
-    println!("{}", dot);
+    let output = match format {
+        GraphFormat::Dot => dot.into_owned(),
+        GraphFormat::Svg => dot_to_svg(&dot)?,
+    };
+
+    match path {
+        Some(path) => {
+            let file = File::create(path)?;
+            writeln!(&file, "{}", output)?;
+        }
+        None => println!("{}", output),
+    }
+
     Ok(())
 }
 
-#[allow(dead_code)]
-fn dot_to_svg<P: AsRef<Path>>(dot: &str, path: P) -> Result<(), Error> {
+/// Lay out the given DOT graph and render it as SVG.
+pub fn dot_to_svg(dot: &str) -> Result<String, Error> {
     let mut parser = DotParser::new(dot);
-    let graph = parser.process().expect("invalid dot string");
+    let graph = parser.process().map_err(Error::Graph)?;
 
     let mut gb = GraphBuilder::new();
     gb.visit_graph(&graph);
@@ -34,14 +63,13 @@ fn dot_to_svg<P: AsRef<Path>>(dot: &str, path: P) -> Result<(), Error> {
 
     let mut writer = SVGWriter::new();
     vg.do_it(false, false, false, &mut writer);
-    let svg = writer.finalize();
-    let file = File::create(path)?;
-    write!(&file, "{}", svg)?;
-
-    Ok(())
+    Ok(writer.finalize())
 }
 
-fn program_to_dot<J: Jet>(program: &CommitNode<J>) -> Result<String, Error> {
+/// Render the given program as a DOT graph.
+///
+/// Subexpressions that encode scribe are compressed into a single node.
+pub fn program_to_dot<J: Jet>(program: &CommitNode<J>) -> Result<String, Error> {
     let mut dot = String::new();
     writeln!(dot, "digraph {{\nranksep=3;")?;
 
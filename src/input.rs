@@ -0,0 +1,129 @@
+use std::fs;
+use std::io::{self, Read};
+use std::path::Path;
+
+use base64::engine::general_purpose;
+use base64::Engine;
+use elements::hex::FromHex;
+use simplicity::elements;
+
+use crate::error::Error;
+
+/// Encoding of input bytes.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Encoding {
+    Hex,
+    Base64,
+    /// Raw bytes without text encoding
+    Binary,
+}
+
+impl Encoding {
+    /// Decode the given bytes from this encoding.
+    ///
+    /// Whitespace around hex and base64 text is ignored.
+    pub fn decode(self, bytes: &[u8]) -> Result<Vec<u8>, Error> {
+        match self {
+            Encoding::Hex => Ok(Vec::<u8>::from_hex(text(bytes)?)?),
+            Encoding::Base64 => Ok(general_purpose::STANDARD.decode(text(bytes)?)?),
+            Encoding::Binary => Ok(bytes.to_vec()),
+        }
+    }
+}
+
+fn text(bytes: &[u8]) -> Result<&str, Error> {
+    std::str::from_utf8(bytes)
+        .map(str::trim)
+        .map_err(|_| Error::Utf8)
+}
+
+/// Read input bytes from the given argument or file.
+///
+/// The argument `-` reads from stdin.
+/// Without an explicit encoding, arguments must be in the default encoding.
+/// Files and stdin which are not valid UTF-8 are read as raw binary;
+/// other files and stdin are read as hex or base64 text,
+/// preferring the default encoding if the text is valid in both.
+pub fn read_input<P: AsRef<Path>>(
+    arg: Option<&str>,
+    file: Option<P>,
+    encoding: Option<Encoding>,
+    default: Encoding,
+) -> Result<Vec<u8>, Error> {
+    let is_argument = file.is_none() && arg != Some("-");
+    let bytes = read_raw(arg, file)?;
+
+    match encoding {
+        Some(encoding) => encoding.decode(&bytes),
+        None if is_argument => default.decode(&bytes),
+        None if std::str::from_utf8(&bytes).is_err() => Ok(bytes),
+        None => default.decode(&bytes).or_else(|error| match default {
+            Encoding::Hex => Encoding::Base64.decode(&bytes).map_err(|_| error),
+            Encoding::Base64 => Encoding::Hex.decode(&bytes).map_err(|_| error),
+            Encoding::Binary => Err(error),
+        }),
+    }
+}
+
+/// Read input text, such as JSON, from the given argument or file.
+///
+/// The argument `-` reads from stdin.
+pub fn read_input_text<P: AsRef<Path>>(
+    arg: Option<&str>,
+    file: Option<P>,
+) -> Result<String, Error> {
+    let bytes = read_raw(arg, file)?;
+    String::from_utf8(bytes).map_err(|_| Error::Utf8)
+}
+
+fn read_raw<P: AsRef<Path>>(arg: Option<&str>, file: Option<P>) -> Result<Vec<u8>, Error> {
+    let bytes = match (arg, file) {
+        (_, Some(path)) => fs::read(path)?,
+        (Some("-"), None) => {
+            let mut bytes = Vec::new();
+            io::stdin().read_to_end(&mut bytes)?;
+            bytes
+        }
+        (Some(arg), None) => arg.as_bytes().to_vec(),
+        (None, None) => return Err(Error::MissingInput),
+    };
+
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read_file(contents: &[u8], default: Encoding) -> Result<Vec<u8>, Error> {
+        let path =
+            std::env::temp_dir().join(format!("hal-simplicity-input-{}", std::process::id()));
+        fs::write(&path, contents).unwrap();
+        let result = read_input(None, Some(&path), None, default);
+        fs::remove_file(&path).unwrap();
+        result
+    }
+
+    #[test]
+    fn read_file_encodings() {
+        // Valid in both encodings, so the default wins
+        assert_eq!(read_file(b"abcd\n", Encoding::Hex).unwrap(), [0xab, 0xcd]);
+        assert_eq!(
+            read_file(b"abcd\n", Encoding::Base64).unwrap(),
+            [0x69, 0xb7, 0x1d]
+        );
+        // Only valid in the other encoding
+        assert_eq!(read_file(b"q80=", Encoding::Hex).unwrap(), [0xab, 0xcd]);
+        assert_eq!(
+            read_file(b"abcdef", Encoding::Base64).unwrap(),
+            [0xab, 0xcd, 0xef]
+        );
+        // Not UTF-8
+        assert_eq!(
+            read_file(&[0xff, 0x00], Encoding::Hex).unwrap(),
+            [0xff, 0x00]
+        );
+        // Text which is neither hex nor base64 is an error, not binary
+        assert!(read_file(b"not hex", Encoding::Hex).is_err());
+    }
+}
@@ -0,0 +1,35 @@
+//! Companion library for the Simplicity language.
+//!
+//! This crate contains the logic behind the `hal-simplicity` binary,
+//! so it can be reused from other Rust tooling.
+
+pub mod address;
+pub mod block;
+pub mod compress;
+pub mod create;
+pub mod decode;
+pub mod encode;
+pub mod env;
+pub mod error;
+pub mod exec;
+pub mod graph;
+pub mod input;
+pub mod peg;
+pub mod prog;
+pub mod pset;
+pub mod script;
+pub mod sighash;
+pub mod sign;
+pub mod tx;
+pub mod unblind;
+pub mod util;
+pub mod verify;
+pub mod witness;
+
+pub use simplicity;
+pub use simplicity::elements;
+
+pub use crate::error::Error;
+pub use crate::prog::ProgramInfo;
+pub use crate::tx::TransactionInfo;
+pub use crate::util::{AddressParamsInfo, GetInfo, Network};
@@ -1,23 +1,29 @@
-mod compress;
-mod decode;
-mod encode;
-mod error;
-mod graph;
-mod tx;
-mod util;
-
-use clap::{Parser, Subcommand};
-use elements::hex::FromHex;
-use simplicity::elements;
-use simplicity::jet::Elements;
-
-use crate::error::Error;
-use crate::tx::TransactionInfo;
-use crate::util::{GetInfo, Network};
+use base64::engine::general_purpose;
+use base64::Engine;
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use elements::hex::{FromHex, ToHex};
+use hal_simplicity::elements;
+use hal_simplicity::elements::secp256k1_zkp::{self, KeyPair, SecretKey, XOnlyPublicKey};
+use hal_simplicity::error::{ErrorClass, ErrorContext};
+use hal_simplicity::input::Encoding;
+use hal_simplicity::simplicity::jet::elements::ElementsEnv;
+use hal_simplicity::simplicity::jet::Elements;
+use hal_simplicity::simplicity::Cmr;
+use hal_simplicity::{
+    address, block, create, decode, env, exec, graph, input, prog, pset, sighash, sign, unblind,
+    verify, witness,
+};
+use hal_simplicity::{AddressParamsInfo, Error, GetInfo, Network, ProgramInfo, TransactionInfo};
 
 #[derive(Parser)]
 #[command(author, version, about, long_about = None)]
 struct Cli {
+    /// Print errors as JSON objects to stderr
+    #[arg(long, global = true)]
+    json_errors: bool,
     #[command(subcommand)]
     command: Command,
 }
@@ -34,14 +40,185 @@ enum Command {
         #[command(subcommand)]
         command: TxCommand,
     },
+    /// Work with Elements blocks
+    Block {
+        #[command(subcommand)]
+        command: BlockCommand,
+    },
+    /// Work with Elements addresses
+    Address {
+        #[command(subcommand)]
+        command: AddressCommand,
+    },
+    /// Work with partially signed Elements transactions (PSET)
+    Pset {
+        #[command(subcommand)]
+        command: PsetCommand,
+    },
+}
+
+#[derive(Subcommand)]
+enum AddressCommand {
+    /// Report network, witness program, blinding key and script type of an address as JSON
+    ///
+    /// For P2TR addresses, the output key can be compared with the key
+    /// of a Simplicity-only address with the given CMR
+    Inspect {
+        /// The address to inspect
+        address: String,
+        /// Hex of the CMR that the output key is compared with
+        #[arg(long)]
+        cmr: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum PsetCommand {
+    /// Decode a PSET to JSON
+    ///
+    /// Tapleaves with the Simplicity leaf version are marked as such,
+    /// programs stored by `pset update-input` are decoded and checked against their CMR,
+    /// and finalized Simplicity spends are decoded like in `tx decode`
+    Decode {
+        #[command(flatten)]
+        input: InputArgs,
+        #[command(flatten)]
+        network: NetworkArgs,
+    },
+    /// Create a PSET from a JSON template and print it as base64
+    ///
+    /// The template is the same as for `tx create`; input script sigs and witnesses are ignored
+    Create {
+        #[command(flatten)]
+        input: TextInputArgs,
+    },
+    /// Attach a Simplicity program to an input of a PSET and print the PSET as base64
+    ///
+    /// The input receives the CMR tapleaf, control block and internal key.
+    /// The program is stored under a proprietary key until the PSET is finalized
+    UpdateInput {
+        #[command(flatten)]
+        input: InputArgs,
+        /// Index of the input to update
+        #[arg(long, default_value_t = 0)]
+        index: u32,
+        /// Base 64 encoding of program, optionally followed by its witness data
+        #[arg(long)]
+        program: String,
+        /// Hex of the x-only internal key (default: unspendable key from BIP 341)
+        #[arg(long, conflicts_with = "control_block")]
+        internal_key: Option<String>,
+        /// Hex of the control block (default: tap tree with the Simplicity leaf alone)
+        ///
+        /// The control block must commit to the output key of the spent output, if known
+        #[arg(long)]
+        control_block: Option<String>,
+        /// JSON object of the output that is spent by the input
+        #[arg(long)]
+        utxo: Option<String>,
+    },
+    /// Assemble the witness stacks of Simplicity spends and print the PSET as base64
+    Finalize {
+        #[command(flatten)]
+        input: InputArgs,
+        /// Index of the input to finalize (default: all inputs with a Simplicity program)
+        #[arg(long)]
+        index: Option<u32>,
+        /// Hex-encoded value for the next witness node (may be repeated)
+        ///
+        /// Without values, the stored program must be followed by its witness data
+        #[arg(long = "witness", requires = "index")]
+        witness: Vec<String>,
+        /// Print the extracted raw transaction as hex instead of the PSET
+        #[arg(long)]
+        extract: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum BlockCommand {
+    /// Decode a raw block to JSON
+    Decode {
+        #[command(flatten)]
+        input: InputArgs,
+        /// Only list the ids of the transactions
+        #[arg(long)]
+        txids: bool,
+        #[command(flatten)]
+        network: NetworkArgs,
+    },
+    /// Decode a raw block header to JSON
+    Header {
+        #[command(flatten)]
+        input: InputArgs,
+        #[command(flatten)]
+        network: NetworkArgs,
+    },
 }
 
 #[derive(Subcommand)]
 enum TxCommand {
     /// Decode a raw transaction to JSON
     Decode {
+        #[command(flatten)]
+        input: InputArgs,
+        /// JSON array of the outputs that are spent by the transaction
+        ///
+        /// Control blocks of script spends are checked against the spent output keys
+        #[arg(long)]
+        utxos: Option<String>,
+        #[command(flatten)]
+        network: NetworkArgs,
+    },
+    /// Create a raw transaction from a JSON template
+    ///
+    /// The template follows the layout of the output of `tx decode`.
+    /// Inputs may instead specify a Simplicity spend by program and control block
+    Create {
+        #[command(flatten)]
+        input: TextInputArgs,
+    },
+    /// Encode the JSON output of `tx decode` as raw transaction
+    Encode {
+        #[command(flatten)]
+        input: TextInputArgs,
+    },
+    /// Compute the Simplicity sighash of a transaction input and report it as JSON
+    ///
+    /// The report includes `sig_all_hash` and its component hashes
+    Sighash {
         /// Raw transaction hex
         hex: String,
+        /// Index of the input that is signed
+        #[arg(long, default_value_t = 0)]
+        input: u32,
+        /// JSON array of the outputs that are spent by the transaction
+        utxos: String,
+        /// Hex of the CMR of the program that is spent
+        #[arg(long)]
+        cmr: String,
+        /// Hex of the control block (default: last element of the input's witness)
+        #[arg(long)]
+        control_block: Option<String>,
+        #[command(flatten)]
+        chain: ChainArgs,
+    },
+    /// Unblind the confidential outputs of a raw transaction and report them as JSON
+    Unblind {
+        #[command(flatten)]
+        input: InputArgs,
+        /// Hex of a blinding private key (may be repeated)
+        #[arg(long = "blinding-key", required = true)]
+        blinding_keys: Vec<String>,
+    },
+    /// Verify the Simplicity spends of a raw transaction and report the result as JSON
+    Verify {
+        /// Raw transaction hex
+        hex: String,
+        /// JSON array of the outputs that are spent by the transaction
+        utxos: String,
+        #[command(flatten)]
+        chain: ChainArgs,
     },
 }
 
@@ -49,40 +226,702 @@ enum TxCommand {
 enum ProgCommand {
     /// List nodes of program
     List {
+        #[command(flatten)]
+        input: InputArgs,
+    },
+    /// Display information about program as JSON
+    ///
+    /// If the program is followed by its witness data, then the finalized program is described
+    Info {
+        /// Base 64 encoding of program
+        base64: String,
+    },
+    /// Run program on the Bit Machine and report the result as JSON
+    ///
+    /// Without a transaction environment, Elements jets run in a dummy environment
+    Run {
+        /// Base 64 encoding of program, followed by its witness data
+        base64: String,
+        /// Raw hex of the spending transaction
+        #[arg(long, requires = "utxos")]
+        tx: Option<String>,
+        /// Index of the input that is spent by the program
+        #[arg(long, default_value_t = 0)]
+        input: u32,
+        /// JSON array of the outputs that are spent by the transaction
+        #[arg(long, requires = "tx")]
+        utxos: Option<String>,
+        /// Hex of the control block (default: last element of the input's witness)
+        #[arg(long)]
+        control_block: Option<String>,
+        #[command(flatten)]
+        chain: ChainArgs,
+    },
+    /// Create the taproot address of a program and report it as JSON
+    Address {
+        /// Base 64 encoding of program
+        #[arg(required_unless_present = "cmr", conflicts_with = "cmr")]
+        base64: Option<String>,
+        /// Hex of the CMR of the program
+        #[arg(long)]
+        cmr: Option<String>,
+        /// Hex of the x-only internal key (default: unspendable key from BIP 341)
+        #[arg(long)]
+        internal_key: Option<String>,
+        /// Hex of an additional tapscript leaf (may be repeated)
+        #[arg(long = "leaf")]
+        leaves: Vec<String>,
+        #[command(flatten)]
+        network: NetworkArgs,
+    },
+    /// Sign program with a BIP 340 key and report the finalized program as JSON
+    ///
+    /// The signature is put into a witness node of the program.
+    /// By default, the signed message is `sig_all_hash` of the given transaction input
+    Sign {
         /// Base 64 encoding of program
         base64: String,
+        /// Hex of the secret key
+        #[arg(
+            long,
+            required_unless_present = "secret_key_file",
+            conflicts_with = "secret_key_file"
+        )]
+        secret_key: Option<String>,
+        /// Read hex of the secret key from file
+        #[arg(long)]
+        secret_key_file: Option<PathBuf>,
+        /// Index of the witness node that receives the signature
+        #[arg(long, default_value_t = 0)]
+        witness_index: usize,
+        /// JSON array of hex-encoded values for the other witness nodes
+        ///
+        /// The entry for the signature is ignored and may be null
+        #[arg(long)]
+        values: Option<String>,
+        /// Hex of the 32-byte message to sign instead of `sig_all_hash`
+        #[arg(long, conflicts_with = "tx")]
+        message: Option<String>,
+        /// Raw hex of the spending transaction
+        #[arg(long, requires = "utxos", required_unless_present = "message")]
+        tx: Option<String>,
+        /// Index of the input that is spent by the program
+        #[arg(long, default_value_t = 0)]
+        input: u32,
+        /// JSON array of the outputs that are spent by the transaction
+        #[arg(long, requires = "tx")]
+        utxos: Option<String>,
+        /// Hex of the control block (default: last element of the input's witness)
+        #[arg(long)]
+        control_block: Option<String>,
+        #[command(flatten)]
+        chain: ChainArgs,
+    },
+    /// Work with the witness data of a program
+    Witness {
+        #[command(subcommand)]
+        command: WitnessCommand,
     },
     /// Visualize program as graph
     ///
-    /// Output is saved to `simplicity.svg`
+    /// Output is printed to stdout unless an output file is given
     Graph {
+        #[command(flatten)]
+        input: InputArgs,
+        /// Output format
+        #[arg(long, value_enum, default_value_t = Format::Dot)]
+        format: Format,
+        /// Output file
+        #[arg(long, short)]
+        output: Option<PathBuf>,
+    },
+}
+
+#[derive(Subcommand)]
+enum WitnessCommand {
+    /// List witness nodes of program with their expected types
+    List {
+        /// Base 64 encoding of program
+        base64: String,
+    },
+    /// Populate witness nodes of program and output the finalized program
+    Finalize {
         /// Base 64 encoding of program
         base64: String,
+        /// JSON array of hex-encoded witness values, one for each witness node
+        values: String,
     },
 }
 
-fn main() -> Result<(), Error> {
-    let cli = Cli::parse();
+#[derive(Args)]
+struct InputArgs {
+    /// Input in the given encoding or `-` to read from stdin
+    #[arg(required_unless_present = "file")]
+    input: Option<String>,
+    /// Read input from file in the given encoding
+    ///
+    /// Without an encoding, files that are not in the default encoding are read as raw binary
+    #[arg(long, conflicts_with = "input")]
+    file: Option<PathBuf>,
+    /// Encoding of the input (default: base64 for programs and PSETs, hex otherwise)
+    #[arg(long, value_enum)]
+    encoding: Option<EncodingArg>,
+}
+
+impl InputArgs {
+    fn read(&self, default: Encoding) -> Result<Vec<u8>, Error> {
+        input::read_input(
+            self.input.as_deref(),
+            self.file.as_ref(),
+            self.encoding.map(Into::into),
+            default,
+        )
+        .with_argument("input")
+    }
+}
+
+#[derive(Args)]
+struct TextInputArgs {
+    /// Input text or `-` to read from stdin
+    #[arg(required_unless_present = "file")]
+    input: Option<String>,
+    /// Read input text from file
+    #[arg(long, conflicts_with = "input")]
+    file: Option<PathBuf>,
+}
 
-    match cli.command {
+impl TextInputArgs {
+    fn read(&self) -> Result<String, Error> {
+        input::read_input_text(self.input.as_deref(), self.file.as_ref()).with_argument("input")
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum EncodingArg {
+    Hex,
+    Base64,
+    /// Raw bytes
+    Binary,
+}
+
+impl From<EncodingArg> for Encoding {
+    fn from(encoding: EncodingArg) -> Self {
+        match encoding {
+            EncodingArg::Hex => Encoding::Hex,
+            EncodingArg::Base64 => Encoding::Base64,
+            EncodingArg::Binary => Encoding::Binary,
+        }
+    }
+}
+
+#[derive(Args)]
+struct NetworkArgs {
+    /// Network whose address format is used
+    #[arg(long, value_enum, default_value_t = NetworkArg::Elementsregtest)]
+    network: NetworkArg,
+    /// JSON object of custom address parameters, instead of a network
+    ///
+    /// Fields: p2pkh_prefix, p2sh_prefix, blinded_prefix, bech_hrp, blech_hrp
+    #[arg(long, conflicts_with = "network")]
+    address_params: Option<String>,
+}
+
+impl NetworkArgs {
+    fn network(&self) -> Result<Network, Error> {
+        match &self.address_params {
+            Some(json) => Ok(custom_network(
+                serde_json::from_str(json).with_argument("address-params")?,
+            )),
+            None => Ok(self.network.into()),
+        }
+    }
+}
+
+#[derive(Args)]
+struct ChainArgs {
+    /// Network of the transaction
+    #[arg(long, value_enum, default_value_t = NetworkArg::Elementsregtest)]
+    network: NetworkArg,
+    /// Hex of the genesis block hash of the chain, overriding the network
+    ///
+    /// Required unless the network is Liquid mainnet
+    #[arg(long)]
+    genesis_hash: Option<String>,
+}
+
+impl ChainArgs {
+    fn genesis_hash(&self) -> Result<elements::BlockHash, Error> {
+        let hex = match (&self.genesis_hash, self.network) {
+            (Some(hex), _) => hex.as_str(),
+            (None, NetworkArg::Liquid) => env::LIQUID_GENESIS_HASH,
+            (None, _) => return Err(Error::MissingGenesisHash),
+        };
+        elements::BlockHash::from_str(hex).with_argument("genesis-hash")
+    }
+}
+
+/// Create a network with the given address parameters.
+///
+/// The parameters are leaked to obtain a static lifetime,
+/// which is fine because the binary creates at most one custom network.
+fn custom_network(params: AddressParamsInfo) -> Network {
+    let params = elements::AddressParams {
+        p2pkh_prefix: params.p2pkh_prefix,
+        p2sh_prefix: params.p2sh_prefix,
+        blinded_prefix: params.blinded_prefix,
+        bech_hrp: Box::leak(params.bech_hrp.into_boxed_str()),
+        blech_hrp: Box::leak(params.blech_hrp.into_boxed_str()),
+    };
+    Network::Custom(Box::leak(Box::new(params)))
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum NetworkArg {
+    /// Liquid mainnet
+    Liquid,
+    /// Liquid testnet
+    Liquidtestnet,
+    /// Elements regtest
+    Elementsregtest,
+}
+
+impl From<NetworkArg> for Network {
+    fn from(network: NetworkArg) -> Self {
+        match network {
+            NetworkArg::Liquid => Network::Liquid,
+            NetworkArg::Liquidtestnet => Network::LiquidTestnet,
+            NetworkArg::Elementsregtest => Network::ElementsRegtest,
+        }
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Format {
+    /// Graphviz DOT
+    Dot,
+    /// Scalable vector graphics
+    Svg,
+}
+
+impl From<Format> for graph::GraphFormat {
+    fn from(format: Format) -> Self {
+        match format {
+            Format::Dot => graph::GraphFormat::Dot,
+            Format::Svg => graph::GraphFormat::Svg,
+        }
+    }
+}
+
+/// Parse a raw transaction from the given hex string.
+fn parse_tx(hex: &str, argument: &str) -> Result<elements::Transaction, Error> {
+    let tx_bytes = Vec::<u8>::from_hex(hex).with_argument(argument)?;
+    decode::decode_elements(&tx_bytes).with_argument(argument)
+}
+
+fn main() {
+    let cli = match Cli::try_parse() {
+        Ok(cli) => cli,
+        // Help and version are printed to stdout and exit successfully
+        Err(error) if !error.use_stderr() => error.exit(),
+        Err(error) => {
+            // The arguments could not be parsed, so look for the flag by hand
+            if std::env::args_os().any(|arg| arg == "--json-errors") {
+                let message = error.to_string();
+                let message = message.lines().next().unwrap_or_default();
+                let message = message.strip_prefix("error: ").unwrap_or(message);
+                print_json_error(&Error::Usage(message.to_owned()));
+            } else {
+                error.print().expect("writing to stderr");
+            }
+            std::process::exit(ErrorClass::Usage.exit_code());
+        }
+    };
+
+    if let Err(error) = run(cli.command) {
+        if cli.json_errors {
+            print_json_error(&error);
+        } else {
+            eprintln!("Error: {}", error);
+        }
+        std::process::exit(error.class().exit_code());
+    }
+}
+
+fn print_json_error(error: &Error) {
+    let info = error.to_info();
+    serde_json::to_writer_pretty(std::io::stderr(), &info).expect("writing to stderr");
+    eprintln!();
+}
+
+fn run(command: Command) -> Result<(), Error> {
+    match command {
         Command::Prog {
-            command: ProgCommand::List { base64 },
+            command: ProgCommand::List { input },
         } => {
-            let program = decode::decode_program::<Elements>(&base64)?;
+            let program = decode::decode_program_bytes::<Elements>(&input.read(Encoding::Base64)?)
+                .with_argument("input")?;
             println!("{}", program);
         }
         Command::Prog {
-            command: ProgCommand::Graph { base64 },
+            command: ProgCommand::Info { base64 },
+        } => {
+            let bytes = general_purpose::STANDARD
+                .decode(&base64)
+                .with_argument("base64")?;
+            let info: ProgramInfo =
+                prog::program_info::<Elements>(&bytes, Network::ElementsRegtest)
+                    .with_argument("base64")?;
+            serde_json::to_writer_pretty(std::io::stdout(), &info)?;
+        }
+        Command::Prog {
+            command:
+                ProgCommand::Run {
+                    base64,
+                    tx,
+                    input,
+                    utxos,
+                    control_block,
+                    chain,
+                },
+        } => {
+            let program =
+                decode::decode_finalized_program::<Elements>(&base64).with_argument("base64")?;
+            let env = match (tx, utxos) {
+                (Some(tx), Some(utxos)) => {
+                    let tx = parse_tx(&tx, "tx")?;
+                    let utxos = env::parse_utxos(&utxos).with_argument("utxos")?;
+                    let control_block = control_block
+                        .map(|hex| env::parse_control_block(&hex))
+                        .transpose()
+                        .with_argument("control-block")?;
+                    let genesis_hash = chain.genesis_hash()?;
+                    env::build_env(tx, utxos, input, program.cmr(), control_block, genesis_hash)?
+                }
+                _ => ElementsEnv::dummy(),
+            };
+            let info = exec::run_program(program.as_ref(), &env);
+            serde_json::to_writer_pretty(std::io::stdout(), &info)?;
+        }
+        Command::Prog {
+            command:
+                ProgCommand::Address {
+                    base64,
+                    cmr,
+                    internal_key,
+                    leaves,
+                    network,
+                },
+        } => {
+            let cmr = match (base64, cmr) {
+                (Some(base64), _) => decode::decode_program::<Elements>(&base64)
+                    .with_argument("base64")?
+                    .cmr(),
+                (None, Some(cmr)) => Cmr::from_str(&cmr).with_argument("cmr")?,
+                (None, None) => return Err(Error::MissingCmr),
+            };
+            let internal_key = match internal_key {
+                Some(key) => XOnlyPublicKey::from_str(&key).with_argument("internal-key")?,
+                None => address::unspendable_internal_key(),
+            };
+            let leaves = leaves
+                .iter()
+                .map(|hex| Ok(elements::Script::from(Vec::<u8>::from_hex(hex)?)))
+                .collect::<Result<Vec<_>, Error>>()
+                .with_argument("leaf")?;
+            let info = address::simplicity_address(cmr, internal_key, &leaves, network.network()?)?;
+            serde_json::to_writer_pretty(std::io::stdout(), &info)?;
+        }
+        Command::Prog {
+            command:
+                ProgCommand::Sign {
+                    base64,
+                    secret_key,
+                    secret_key_file,
+                    witness_index,
+                    values,
+                    message,
+                    tx,
+                    input,
+                    utxos,
+                    control_block,
+                    chain,
+                },
+        } => {
+            let program = decode::decode_program::<Elements>(&base64).with_argument("base64")?;
+            let secret_key = match (secret_key, secret_key_file) {
+                (Some(key), _) => key,
+                (None, Some(path)) => std::fs::read_to_string(path)
+                    .with_argument("secret-key-file")?
+                    .trim()
+                    .to_owned(),
+                (None, None) => return Err(Error::MissingInput.with_argument("secret-key")),
+            };
+            let secp = secp256k1_zkp::Secp256k1::new();
+            let keypair =
+                KeyPair::from_seckey_str(&secp, &secret_key).with_argument("secret-key")?;
+            let values: Vec<Option<String>> = match values {
+                Some(json) => serde_json::from_str(&json).with_argument("values")?,
+                None => vec![],
+            };
+            let control_block = control_block
+                .map(|hex| env::parse_control_block(&hex))
+                .transpose()
+                .with_argument("control-block")?;
+
+            let (message, control_block) = match (message, tx, utxos) {
+                (Some(message), _, _) => (
+                    sign::parse_message(&message).with_argument("message")?,
+                    control_block,
+                ),
+                (None, Some(tx), Some(utxos)) => {
+                    let tx = parse_tx(&tx, "tx")?;
+                    let utxos = env::parse_utxos(&utxos).with_argument("utxos")?;
+                    let genesis_hash = chain.genesis_hash()?;
+                    let control_block = match control_block {
+                        Some(control_block) => control_block,
+                        None => env::witness_control_block(&tx, input).with_argument("tx")?,
+                    };
+                    let env = env::build_env(
+                        tx,
+                        utxos,
+                        input,
+                        program.cmr(),
+                        Some(control_block.clone()),
+                        genesis_hash,
+                    )?;
+                    (sighash::sig_all_hash(&env)?, Some(control_block))
+                }
+                _ => return Err(Error::MissingInput.with_argument("tx")),
+            };
+            let info = sign::sign_program(
+                program.as_ref(),
+                &keypair,
+                message,
+                witness_index,
+                &values,
+                control_block.as_ref(),
+            )?;
+            serde_json::to_writer_pretty(std::io::stdout(), &info)?;
+        }
+        Command::Prog {
+            command: ProgCommand::Witness { command },
+        } => match command {
+            WitnessCommand::List { base64 } => {
+                let program =
+                    decode::decode_program::<Elements>(&base64).with_argument("base64")?;
+                let info = witness::list_witness_slots(program.as_ref());
+                serde_json::to_writer_pretty(std::io::stdout(), &info)?;
+            }
+            WitnessCommand::Finalize { base64, values } => {
+                let program =
+                    decode::decode_program::<Elements>(&base64).with_argument("base64")?;
+                let values: Vec<String> = serde_json::from_str(&values).with_argument("values")?;
+                let program = witness::finalize_with_hex(program.as_ref(), &values)
+                    .with_argument("values")?;
+                let info = witness::finalized_program_info(program.as_ref())?;
+                serde_json::to_writer_pretty(std::io::stdout(), &info)?;
+            }
+        },
+        Command::Prog {
+            command:
+                ProgCommand::Graph {
+                    input,
+                    format,
+                    output,
+                },
+        } => {
+            let program = decode::decode_program_bytes::<Elements>(&input.read(Encoding::Base64)?)
+                .with_argument("input")?;
+            graph::visualize(program.as_ref(), format.into(), output)?;
+        }
+        Command::Block { command } => match command {
+            BlockCommand::Decode {
+                input,
+                txids,
+                network,
+            } => {
+                let block: elements::Block =
+                    decode::decode_elements(&input.read(Encoding::Hex)?).with_argument("input")?;
+                let info: block::BlockInfo = if txids {
+                    block::BlockInfo::with_txids(&block, network.network()?)
+                } else {
+                    block.get_info(network.network()?)
+                };
+                serde_json::to_writer_pretty(std::io::stdout(), &info)?;
+            }
+            BlockCommand::Header { input, network } => {
+                let header: elements::BlockHeader =
+                    decode::decode_elements(&input.read(Encoding::Hex)?).with_argument("input")?;
+                let info: block::BlockHeaderInfo = header.get_info(network.network()?);
+                serde_json::to_writer_pretty(std::io::stdout(), &info)?;
+            }
+        },
+        Command::Address {
+            command: AddressCommand::Inspect { address, cmr },
         } => {
-            let program = decode::decode_program::<Elements>(&base64)?;
-            graph::visualize(program.as_ref())?;
+            let address = elements::Address::from_str(&address).with_argument("address")?;
+            let cmr = cmr
+                .map(|cmr| Cmr::from_str(&cmr))
+                .transpose()
+                .with_argument("cmr")?;
+            let info = address::inspect_address(&address, cmr)?;
+            serde_json::to_writer_pretty(std::io::stdout(), &info)?;
         }
+        Command::Pset { command } => match command {
+            PsetCommand::Decode { input, network } => {
+                let pset: elements::pset::PartiallySignedTransaction =
+                    decode::decode_elements(&input.read(Encoding::Base64)?)
+                        .with_argument("input")?;
+                let info: pset::PsetInfo = pset.get_info(network.network()?);
+                serde_json::to_writer_pretty(std::io::stdout(), &info)?;
+            }
+            PsetCommand::Create { input } => {
+                let template: create::TransactionTemplate =
+                    serde_json::from_str(&input.read()?).with_argument("input")?;
+                let pset = pset::create_pset(&template).with_argument("input")?;
+                println!("{}", pset::encode_pset(&pset));
+            }
+            PsetCommand::UpdateInput {
+                input,
+                index,
+                program,
+                internal_key,
+                control_block,
+                utxo,
+            } => {
+                let mut pset: elements::pset::PartiallySignedTransaction =
+                    decode::decode_elements(&input.read(Encoding::Base64)?)
+                        .with_argument("input")?;
+                let program_bytes = general_purpose::STANDARD
+                    .decode(&program)
+                    .with_argument("program")?;
+                let internal_key = match internal_key {
+                    Some(key) => XOnlyPublicKey::from_str(&key).with_argument("internal-key")?,
+                    None => address::unspendable_internal_key(),
+                };
+                let control_block = control_block
+                    .map(|hex| env::parse_control_block(&hex))
+                    .transpose()
+                    .with_argument("control-block")?;
+                let utxo = utxo
+                    .map(|json| {
+                        let utxo: env::UtxoInfo = serde_json::from_str(&json)?;
+                        utxo.to_txout()
+                    })
+                    .transpose()
+                    .with_argument("utxo")?;
+                pset::update_input(
+                    &mut pset,
+                    index,
+                    program_bytes,
+                    internal_key,
+                    control_block,
+                    utxo,
+                )?;
+                println!("{}", pset::encode_pset(&pset));
+            }
+            PsetCommand::Finalize {
+                input,
+                index,
+                witness,
+                extract,
+            } => {
+                let mut pset: elements::pset::PartiallySignedTransaction =
+                    decode::decode_elements(&input.read(Encoding::Base64)?)
+                        .with_argument("input")?;
+                match index {
+                    Some(index) => {
+                        let values = (!witness.is_empty()).then_some(witness.as_slice());
+                        pset::finalize_input(&mut pset, index, values)?;
+                    }
+                    None => {
+                        let indices: Vec<u32> = (0..pset.n_inputs() as u32)
+                            .filter(|&i| pset::has_program(&pset.inputs()[i as usize]))
+                            .collect();
+                        for index in indices {
+                            pset::finalize_input(&mut pset, index, None)?;
+                        }
+                    }
+                }
+                if extract {
+                    let tx = pset.extract_tx()?;
+                    println!("{}", elements::encode::serialize(&tx).to_hex());
+                } else {
+                    println!("{}", pset::encode_pset(&pset));
+                }
+            }
+        },
         Command::Tx { command } => match command {
-            TxCommand::Decode { hex } => {
-                let tx_bytes = Vec::<u8>::from_hex(hex.as_str()).expect("hex error");
-                let tx: elements::Transaction = elements::encode::deserialize(&tx_bytes)?;
-                let info: TransactionInfo = tx.get_info(Network::ElementsRegtest);
+            TxCommand::Decode {
+                input,
+                utxos,
+                network,
+            } => {
+                let tx: elements::Transaction =
+                    decode::decode_elements(&input.read(Encoding::Hex)?).with_argument("input")?;
+                let mut info: TransactionInfo = tx.get_info(network.network()?);
+                if let Some(utxos) = utxos {
+                    let utxos = env::parse_utxos(&utxos).with_argument("utxos")?;
+                    info.check_spent_outputs(&tx, &utxos)
+                        .with_argument("utxos")?;
+                }
+                serde_json::to_writer_pretty(std::io::stdout(), &info)?;
+            }
+            TxCommand::Create { input } => {
+                let template: create::TransactionTemplate =
+                    serde_json::from_str(&input.read()?).with_argument("input")?;
+                let tx = template.to_transaction().with_argument("input")?;
+                println!("{}", elements::encode::serialize(&tx).to_hex());
+            }
+            TxCommand::Encode { input } => {
+                let info: TransactionInfo =
+                    serde_json::from_str(&input.read()?).with_argument("input")?;
+                let tx = info.to_transaction().with_argument("input")?;
+                println!("{}", elements::encode::serialize(&tx).to_hex());
+            }
+            TxCommand::Sighash {
+                hex,
+                input,
+                utxos,
+                cmr,
+                control_block,
+                chain,
+            } => {
+                let tx = parse_tx(&hex, "hex")?;
+                let utxos = env::parse_utxos(&utxos).with_argument("utxos")?;
+                let cmr = Cmr::from_str(&cmr).with_argument("cmr")?;
+                let control_block = control_block
+                    .map(|hex| env::parse_control_block(&hex))
+                    .transpose()
+                    .with_argument("control-block")?;
+                let genesis_hash = chain.genesis_hash()?;
+                let env = env::build_env(tx, utxos, input, cmr, control_block, genesis_hash)?;
+                let info = sighash::sighash_info(&env)?;
+                serde_json::to_writer_pretty(std::io::stdout(), &info)?;
+            }
+            TxCommand::Unblind {
+                input,
+                blinding_keys,
+            } => {
+                let tx: elements::Transaction =
+                    decode::decode_elements(&input.read(Encoding::Hex)?).with_argument("input")?;
+                let blinding_keys = blinding_keys
+                    .iter()
+                    .map(|hex| SecretKey::from_str(hex))
+                    .collect::<Result<Vec<_>, _>>()
+                    .with_argument("blinding-key")?;
+                let info = unblind::unblind_outputs(&tx, &blinding_keys);
+                serde_json::to_writer_pretty(std::io::stdout(), &info)?;
+            }
+            TxCommand::Verify { hex, utxos, chain } => {
+                let tx = parse_tx(&hex, "hex")?;
+                let utxos = env::parse_utxos(&utxos).with_argument("utxos")?;
+                let genesis_hash = chain.genesis_hash()?;
+                let info = verify::verify_transaction(&tx, &utxos, genesis_hash);
                 serde_json::to_writer_pretty(std::io::stdout(), &info)?;
+                if !info.success {
+                    std::process::exit(1);
+                }
             }
         },
     }
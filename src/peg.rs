@@ -0,0 +1,189 @@
+use elements::bitcoin;
+use elements::hashes::Hash;
+use elements::hex::ToHex;
+use elements::script::Instruction;
+use serde::{Deserialize, Serialize};
+use simplicity::elements;
+
+use crate::util::{GetInfo, Network};
+
+#[derive(Clone, PartialEq, Eq, Debug, Deserialize, Serialize)]
+#[non_exhaustive]
+pub struct PeginInfo {
+    pub parent_genesis_hash: bitcoin::BlockHash,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parent_network: Option<bitcoin::Network>,
+    pub mainchain_outpoint: bitcoin::OutPoint,
+    pub value: u64,
+    pub asset: String,
+    pub claim_script: String,
+    pub mainchain_tx: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mainchain_txid: Option<bitcoin::Txid>,
+    pub merkle_proof: String,
+    pub referenced_block: bitcoin::BlockHash,
+}
+
+impl<'tx> GetInfo<PeginInfo> for elements::PeginData<'tx> {
+    fn get_info(&self, _network: Network) -> PeginInfo {
+        let mainchain_tx: Option<bitcoin::Transaction> =
+            bitcoin::consensus::deserialize(self.tx).ok();
+
+        PeginInfo {
+            parent_genesis_hash: self.genesis_hash,
+            parent_network: parent_network(self.genesis_hash),
+            mainchain_outpoint: self.outpoint,
+            value: self.value,
+            asset: self.asset.to_string(),
+            claim_script: self.claim_script.to_hex(),
+            mainchain_tx: self.tx.to_hex(),
+            mainchain_txid: mainchain_tx.map(|tx| tx.txid()),
+            merkle_proof: self.merkle_proof.to_hex(),
+            referenced_block: self.referenced_block,
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Debug, Deserialize, Serialize)]
+#[non_exhaustive]
+pub struct PegoutInfo {
+    pub parent_genesis_hash: bitcoin::BlockHash,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parent_network: Option<bitcoin::Network>,
+    pub script_pub_key: String,
+    pub asm: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub address: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub extra_data: Vec<String>,
+}
+
+/// Return the Bitcoin network with the given genesis block hash, if it is known.
+fn parent_network(genesis_hash: bitcoin::BlockHash) -> Option<bitcoin::Network> {
+    [
+        bitcoin::Network::Bitcoin,
+        bitcoin::Network::Testnet,
+        bitcoin::Network::Signet,
+        bitcoin::Network::Regtest,
+    ]
+    .into_iter()
+    .find(|network| {
+        bitcoin::blockdata::constants::genesis_block(*network).block_hash() == genesis_hash
+    })
+}
+
+/// Decode the given scriptPubKey as peg-out.
+///
+/// Peg-out scripts consist of `OP_RETURN`, followed by pushes of the genesis block hash
+/// of the parent chain, the destination scriptPubKey on the parent chain and optional extra data.
+pub fn pegout_info(script: &elements::Script) -> Option<PegoutInfo> {
+    if !script.is_op_return() {
+        return None;
+    }
+
+    let mut pushes = script.instructions().skip(1).map(|ins| match ins {
+        Ok(Instruction::PushBytes(bytes)) => Some(bytes),
+        _ => None,
+    });
+    let genesis_bytes = pushes.next()??;
+    let parent_genesis_hash = bitcoin::BlockHash::from_slice(genesis_bytes).ok()?;
+    let destination = bitcoin::ScriptBuf::from(pushes.next()??.to_vec());
+    let extra_data = pushes.collect::<Option<Vec<_>>>()?;
+
+    let parent_network = parent_network(parent_genesis_hash);
+    let address = parent_network.and_then(|network| {
+        bitcoin::Address::from_script(&destination, network)
+            .ok()
+            .map(|address| address.to_string())
+    });
+
+    Some(PegoutInfo {
+        parent_genesis_hash,
+        parent_network,
+        script_pub_key: destination.to_hex_string(),
+        asm: destination.to_asm_string(),
+        address,
+        extra_data: extra_data.into_iter().map(|data| data.to_hex()).collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use elements::opcodes::all::{OP_CHECKSIG, OP_RETURN};
+    use elements::script::Builder;
+
+    use super::*;
+
+    /// P2WPKH scriptPubKey on the parent chain
+    const DESTINATION: [u8; 22] = [
+        0x00, 0x14, 0x75, 0x1e, 0x76, 0xe8, 0x19, 0x91, 0x96, 0xd4, 0x54, 0x94, 0x1c, 0x45, 0xd1,
+        0xb3, 0xa3, 0x23, 0xf1, 0x43, 0x3b, 0xd6,
+    ];
+
+    fn pegout_script(genesis_hash: &[u8], extra_data: &[&[u8]]) -> elements::Script {
+        let mut builder = Builder::new()
+            .push_opcode(OP_RETURN)
+            .push_slice(genesis_hash)
+            .push_slice(&DESTINATION);
+        for data in extra_data {
+            builder = builder.push_slice(data);
+        }
+        builder.into_script()
+    }
+
+    fn bitcoin_genesis_hash() -> bitcoin::BlockHash {
+        bitcoin::blockdata::constants::genesis_block(bitcoin::Network::Bitcoin).block_hash()
+    }
+
+    #[test]
+    fn pegout_without_extra_data() {
+        let genesis_hash = bitcoin_genesis_hash();
+        let info = pegout_info(&pegout_script(&genesis_hash.to_byte_array(), &[])).unwrap();
+        assert_eq!(info.parent_genesis_hash, genesis_hash);
+        assert_eq!(info.parent_network, Some(bitcoin::Network::Bitcoin));
+        assert_eq!(info.script_pub_key, DESTINATION.to_hex());
+        assert_eq!(
+            info.address.as_deref(),
+            Some("bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4")
+        );
+        assert!(info.extra_data.is_empty());
+    }
+
+    #[test]
+    fn pegout_with_extra_data() {
+        let genesis_hash = bitcoin_genesis_hash();
+        let script = pegout_script(&genesis_hash.to_byte_array(), &[&[0xaa; 3], &[0xbb; 33]]);
+        let info = pegout_info(&script).unwrap();
+        assert_eq!(info.script_pub_key, DESTINATION.to_hex());
+        assert_eq!(
+            info.extra_data,
+            vec![[0xaau8; 3].to_hex(), [0xbbu8; 33].to_hex()]
+        );
+    }
+
+    #[test]
+    fn pegout_unknown_parent() {
+        let info = pegout_info(&pegout_script(&[0x11; 32], &[])).unwrap();
+        assert_eq!(info.parent_network, None);
+        assert_eq!(info.address, None);
+        assert_eq!(info.script_pub_key, DESTINATION.to_hex());
+    }
+
+    #[test]
+    fn not_pegout() {
+        let genesis_hash = bitcoin_genesis_hash().to_byte_array();
+        // Genesis block hash of the wrong length
+        assert_eq!(pegout_info(&pegout_script(&genesis_hash[1..], &[])), None);
+        // Missing destination
+        let script = Builder::new()
+            .push_opcode(OP_RETURN)
+            .push_slice(&genesis_hash)
+            .into_script();
+        assert_eq!(pegout_info(&script), None);
+        // Extra data that is not a push
+        let script = Builder::from(pegout_script(&genesis_hash, &[]).into_bytes())
+            .push_opcode(OP_CHECKSIG)
+            .into_script();
+        assert_eq!(pegout_info(&script), None);
+    }
+}
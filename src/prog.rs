@@ -0,0 +1,86 @@
+use std::fmt::Write;
+
+use serde::{Deserialize, Serialize};
+use simplicity::dag::{DagLike, MaxSharing};
+use simplicity::jet::Jet;
+use simplicity::{CommitNode, RedeemNode};
+
+use crate::decode;
+use crate::error::Error;
+use crate::util::{GetInfo, Network};
+
+#[derive(Clone, PartialEq, Eq, Debug, Deserialize, Serialize)]
+#[non_exhaustive]
+pub struct ProgramInfo {
+    pub cmr: String,
+    pub imr: Option<String>,
+    pub amr: Option<String>,
+    pub source_type: String,
+    pub target_type: String,
+    pub node_count: usize,
+    pub is_finalized: bool,
+}
+
+impl<J: Jet> GetInfo<ProgramInfo> for CommitNode<J> {
+    fn get_info(&self, _network: Network) -> ProgramInfo {
+        ProgramInfo {
+            cmr: self.cmr().to_string(),
+            imr: self.imr().map(|imr| imr.to_string()),
+            amr: self.amr().map(|amr| amr.to_string()),
+            source_type: self.arrow().source.to_string(),
+            target_type: self.arrow().target.to_string(),
+            node_count: self.post_order_iter::<MaxSharing<_>>().count(),
+            is_finalized: false,
+        }
+    }
+}
+
+impl<J: Jet> GetInfo<ProgramInfo> for RedeemNode<J> {
+    fn get_info(&self, _network: Network) -> ProgramInfo {
+        ProgramInfo {
+            cmr: self.cmr().to_string(),
+            imr: Some(self.imr().to_string()),
+            amr: Some(self.amr().to_string()),
+            source_type: self.arrow().source.to_string(),
+            target_type: self.arrow().target.to_string(),
+            node_count: self.post_order_iter::<MaxSharing<_>>().count(),
+            is_finalized: true,
+        }
+    }
+}
+
+/// Describe the program that is encoded in the given bytes.
+///
+/// If the program is followed by its witness data, then the finalized program is described.
+/// The unfinalized program is described only if no set bits follow the program.
+pub fn program_info<J: Jet>(bytes: &[u8], network: Network) -> Result<ProgramInfo, Error> {
+    match decode::decode_finalized_program_bytes::<J>(bytes) {
+        Ok(program) => Ok(program.get_info(network)),
+        Err(error) => {
+            if decode::has_witness_data::<J>(bytes)? {
+                return Err(error);
+            }
+            let program = decode::decode_program_bytes::<J>(bytes)?;
+            Ok(program.get_info(network))
+        }
+    }
+}
+
+/// List the nodes of the given program in post order.
+///
+/// Each node is printed with its index, followed by the indices of its children.
+pub fn list_nodes<J: Jet>(program: &RedeemNode<J>) -> Vec<String> {
+    program
+        .post_order_iter::<MaxSharing<_>>()
+        .map(|item| {
+            let mut line = format!("{}: {}", item.index, item.node.inner());
+            if let Some(i_abs) = item.left_index {
+                write!(line, " {}", i_abs).expect("writing to string");
+            }
+            if let Some(j_abs) = item.right_index {
+                write!(line, " {}", j_abs).expect("writing to string");
+            }
+            line
+        })
+        .collect()
+}
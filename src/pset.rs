@@ -0,0 +1,579 @@
+use std::collections::BTreeMap;
+
+use base64::engine::general_purpose;
+use base64::Engine;
+use elements::hex::ToHex;
+use elements::pset::raw::ProprietaryKey;
+use elements::pset::PartiallySignedTransaction;
+use elements::secp256k1_zkp::{self, XOnlyPublicKey};
+use elements::taproot::{ControlBlock, TapLeafHash};
+use serde::{Deserialize, Serialize};
+use simplicity::elements;
+use simplicity::jet::Elements;
+use simplicity::Cmr;
+
+use crate::create::{self, TransactionTemplate};
+use crate::error::{Error, ErrorContext};
+use crate::prog::{self, ProgramInfo};
+use crate::tx::{OutputScriptInfo, ScriptSpendInfo, ScriptSpendWitness};
+use crate::util::{self, GetInfo, Network};
+use crate::{address, decode, script, witness};
+
+#[derive(Clone, PartialEq, Eq, Debug, Deserialize, Serialize)]
+#[non_exhaustive]
+pub struct PsetInfo {
+    pub global: GlobalInfo,
+    pub inputs: Vec<PsetInputInfo>,
+    pub outputs: Vec<PsetOutputInfo>,
+    pub is_finalized: bool,
+}
+
+impl GetInfo<PsetInfo> for PartiallySignedTransaction {
+    fn get_info(&self, network: Network) -> PsetInfo {
+        let inputs: Vec<PsetInputInfo> =
+            self.inputs().iter().map(|i| i.get_info(network)).collect();
+
+        PsetInfo {
+            global: self.global.get_info(network),
+            is_finalized: inputs.iter().all(|i| i.is_finalized),
+            inputs,
+            outputs: self.outputs().iter().map(|o| o.get_info(network)).collect(),
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Debug, Deserialize, Serialize)]
+#[non_exhaustive]
+pub struct GlobalInfo {
+    pub pset_version: u32,
+    pub tx_version: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fallback_locktime: Option<elements::LockTime>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tx_modifiable: Option<u8>,
+    pub xpub_count: usize,
+    pub scalar_count: usize,
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub proprietary: Vec<ProprietaryInfo>,
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub unknown: Vec<UnknownInfo>,
+}
+
+impl GetInfo<GlobalInfo> for elements::pset::Global {
+    fn get_info(&self, _network: Network) -> GlobalInfo {
+        GlobalInfo {
+            pset_version: self.version,
+            tx_version: self.tx_data.version,
+            fallback_locktime: self.tx_data.fallback_locktime,
+            tx_modifiable: self.tx_data.tx_modifiable,
+            xpub_count: self.xpub.len(),
+            scalar_count: self.scalars.len(),
+            proprietary: proprietary_info(&self.proprietary),
+            unknown: self
+                .unknown
+                .iter()
+                .map(|(key, value)| UnknownInfo {
+                    key_type: key.type_value,
+                    key: key.key.to_hex(),
+                    value: value.to_hex(),
+                })
+                .collect(),
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Debug, Deserialize, Serialize)]
+#[non_exhaustive]
+pub struct ProprietaryInfo {
+    pub prefix: String,
+    pub subtype: u8,
+    pub key: String,
+    pub value: String,
+}
+
+fn proprietary_info(map: &BTreeMap<ProprietaryKey, Vec<u8>>) -> Vec<ProprietaryInfo> {
+    map.iter()
+        .map(|(key, value)| ProprietaryInfo {
+            prefix: String::from_utf8(key.prefix.clone()).unwrap_or_else(|_| key.prefix.to_hex()),
+            subtype: key.subtype,
+            key: key.key.to_hex(),
+            value: value.to_hex(),
+        })
+        .collect()
+}
+
+#[derive(Clone, PartialEq, Eq, Debug, Deserialize, Serialize)]
+#[non_exhaustive]
+pub struct UnknownInfo {
+    pub key_type: u8,
+    pub key: String,
+    pub value: String,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug, Deserialize, Serialize)]
+#[non_exhaustive]
+pub struct PsetInputInfo {
+    pub previous_txid: elements::Txid,
+    pub previous_output_index: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sequence: Option<elements::Sequence>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub witness_utxo: Option<PsetUtxoInfo>,
+    pub has_non_witness_utxo: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tap_internal_key: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tap_merkle_root: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tap_key_sig: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub tap_script_sigs: Vec<TapScriptSigInfo>,
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub tap_leaves: Vec<TapLeafInfo>,
+    pub is_finalized: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub final_script_witness: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub script_spend: Option<ScriptSpendInfo>,
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub proprietary: Vec<ProprietaryInfo>,
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub programs: Vec<PsetProgramInfo>,
+}
+
+impl GetInfo<PsetInputInfo> for elements::pset::Input {
+    fn get_info(&self, network: Network) -> PsetInputInfo {
+        let final_script_witness = self.final_script_witness.as_ref();
+
+        PsetInputInfo {
+            previous_txid: self.previous_txid,
+            previous_output_index: self.previous_output_index,
+            sequence: self.sequence,
+            witness_utxo: self.witness_utxo.as_ref().map(|utxo| PsetUtxoInfo {
+                script_pub_key: utxo.script_pubkey.get_info(network),
+                value: utxo.value.explicit(),
+                asset: utxo.asset.explicit().map(|asset| asset.to_string()),
+            }),
+            has_non_witness_utxo: self.non_witness_utxo.is_some(),
+            tap_internal_key: self.tap_internal_key.map(|key| key.to_hex()),
+            tap_merkle_root: self.tap_merkle_root.map(|root| root.to_hex()),
+            tap_key_sig: self.tap_key_sig.map(|sig| sig.to_vec().to_hex()),
+            tap_script_sigs: self
+                .tap_script_sigs
+                .iter()
+                .map(|((key, leaf_hash), sig)| TapScriptSigInfo {
+                    public_key: key.to_hex(),
+                    leaf_hash: leaf_hash.to_hex(),
+                    signature: sig.to_vec().to_hex(),
+                })
+                .collect(),
+            tap_leaves: self
+                .tap_scripts
+                .iter()
+                .map(|(control_block, (script, version))| {
+                    tap_leaf_info(control_block, script, *version)
+                })
+                .collect(),
+            is_finalized: self.final_script_sig.is_some() || final_script_witness.is_some(),
+            final_script_witness: final_script_witness
+                .map(|stack| stack.iter().map(|x| x.to_hex()).collect()),
+            script_spend: final_script_witness
+                .and_then(|stack| ScriptSpendWitness::new(stack))
+                .map(|spend| spend.get_info(network)),
+            proprietary: proprietary_info(&self.proprietary),
+            programs: self
+                .proprietary
+                .iter()
+                .filter(|(key, _)| {
+                    key.prefix == PROPRIETARY_PREFIX && key.subtype == PROPRIETARY_PROGRAM
+                })
+                .map(|(key, value)| stored_program_info(&key.key, value, network))
+                .collect(),
+        }
+    }
+}
+
+/// Simplicity program that is stored in a PSET input until finalization.
+#[derive(Clone, PartialEq, Eq, Debug, Deserialize, Serialize)]
+#[non_exhaustive]
+pub struct PsetProgramInfo {
+    pub cmr: String,
+    pub program: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub info: Option<ProgramInfo>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Describe the program that is stored under the given CMR.
+///
+/// Programs that fail to decode or whose CMR differs from the key are reported with an error.
+fn stored_program_info(cmr: &[u8], program: &[u8], network: Network) -> PsetProgramInfo {
+    let info = prog::program_info::<Elements>(program, network).and_then(|info| {
+        let key_cmr = <[u8; 32]>::try_from(cmr).ok().map(Cmr::from_byte_array);
+        match key_cmr {
+            Some(key_cmr) if key_cmr.to_string() == info.cmr => Ok(info),
+            _ => Err(Error::CmrMismatch(info.cmr, cmr.to_hex())),
+        }
+    });
+
+    PsetProgramInfo {
+        cmr: cmr.to_hex(),
+        program: general_purpose::STANDARD.encode(program),
+        error: info.as_ref().err().map(|e| e.to_string()),
+        info: info.ok(),
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Debug, Deserialize, Serialize)]
+#[non_exhaustive]
+pub struct PsetUtxoInfo {
+    pub script_pub_key: OutputScriptInfo,
+    pub value: Option<u64>,
+    pub asset: Option<String>,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug, Deserialize, Serialize)]
+#[non_exhaustive]
+pub struct TapScriptSigInfo {
+    pub public_key: String,
+    pub leaf_hash: String,
+    pub signature: String,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug, Deserialize, Serialize)]
+#[non_exhaustive]
+pub struct TapLeafInfo {
+    pub leaf_version: String,
+    pub script: String,
+    /// ASM of the leaf script, unless it is a Simplicity leaf
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub asm: Option<String>,
+    pub control_block: String,
+    pub internal_key: String,
+    pub merkle_path: Vec<String>,
+    pub tapleaf_hash: String,
+    /// Merkle root that is reconstructed from the tapleaf hash and the merkle path
+    pub merkle_root: String,
+    pub is_simplicity: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cmr: Option<String>,
+}
+
+/// Describe the given tapleaf.
+///
+/// Simplicity leaves commit to the CMR of their program, which is reported separately.
+/// Leaves are classified and hashed in the same way as in `tx decode`.
+fn tap_leaf_info(
+    control_block: &elements::taproot::ControlBlock,
+    script: &elements::Script,
+    version: elements::taproot::LeafVersion,
+) -> TapLeafInfo {
+    let spend = ScriptSpendWitness::from_leaf(script.as_bytes(), control_block.clone());
+    let is_simplicity = spend.is_simplicity_leaf();
+    let commitment = spend.leaf_commitment();
+
+    TapLeafInfo {
+        leaf_version: version.as_u8().to_hex(),
+        script: script.to_hex(),
+        asm: commitment.asm,
+        control_block: control_block.serialize().to_hex(),
+        internal_key: control_block.internal_key.to_hex(),
+        merkle_path: control_block
+            .merkle_branch
+            .as_inner()
+            .iter()
+            .map(|h| h.to_hex())
+            .collect(),
+        tapleaf_hash: commitment.tapleaf_hash.to_hex(),
+        merkle_root: commitment.merkle_root.to_hex(),
+        is_simplicity,
+        cmr: if is_simplicity {
+            Some(script.to_hex())
+        } else {
+            None
+        },
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Debug, Deserialize, Serialize)]
+#[non_exhaustive]
+pub struct PsetOutputInfo {
+    pub script_pub_key: OutputScriptInfo,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub amount: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub amount_commitment: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub asset: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub asset_commitment: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub blinding_key: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub blinder_index: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tap_internal_key: Option<String>,
+    pub has_tap_tree: bool,
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub proprietary: Vec<ProprietaryInfo>,
+}
+
+impl GetInfo<PsetOutputInfo> for elements::pset::Output {
+    fn get_info(&self, network: Network) -> PsetOutputInfo {
+        PsetOutputInfo {
+            script_pub_key: self.script_pubkey.get_info(network),
+            amount: self.amount,
+            amount_commitment: self.amount_comm.map(|c| c.to_string()),
+            asset: self.asset.map(|asset| asset.to_string()),
+            asset_commitment: self.asset_comm.map(|c| c.to_string()),
+            blinding_key: self.blinding_key.map(|key| key.to_string()),
+            blinder_index: self.blinder_index,
+            tap_internal_key: self.tap_internal_key.map(|key| key.to_hex()),
+            has_tap_tree: self.tap_tree.is_some(),
+            proprietary: proprietary_info(&self.proprietary),
+        }
+    }
+}
+
+/// Prefix of the proprietary keys under which Simplicity data is stored.
+pub const PROPRIETARY_PREFIX: &[u8] = b"simplicity";
+/// Subtype of the proprietary input key that maps the CMR of a program to its encoding.
+pub const PROPRIETARY_PROGRAM: u8 = 0x00;
+
+fn program_key(cmr: &[u8]) -> ProprietaryKey {
+    ProprietaryKey {
+        prefix: PROPRIETARY_PREFIX.to_vec(),
+        subtype: PROPRIETARY_PROGRAM,
+        key: cmr.to_vec(),
+    }
+}
+
+/// Return the base64 encoding of the given PSET.
+pub fn encode_pset(pset: &PartiallySignedTransaction) -> String {
+    general_purpose::STANDARD.encode(elements::encode::serialize(pset))
+}
+
+/// Create a PSET from the given template.
+///
+/// Script sigs and witnesses of the inputs are ignored;
+/// they are added by updating and finalizing the PSET.
+pub fn create_pset(template: &TransactionTemplate) -> Result<PartiallySignedTransaction, Error> {
+    let tx = template.to_unsigned_transaction()?;
+    Ok(PartiallySignedTransaction::from_tx(tx))
+}
+
+fn pset_input(
+    pset: &mut PartiallySignedTransaction,
+    index: u32,
+) -> Result<&mut elements::pset::Input, Error> {
+    let n_inputs = pset.n_inputs();
+    pset.inputs_mut()
+        .get_mut(index as usize)
+        .ok_or(Error::InputIndex(index, n_inputs))
+}
+
+/// Attach a Simplicity program to the given input of the PSET.
+///
+/// The input receives the tapleaf that commits to the CMR of the program, its control block
+/// and the internal key. If no control block is given, then the tap tree consists of the
+/// Simplicity leaf alone. The control block must commit to the output key of the spent
+/// output, if that output is known. The program itself is stored under a proprietary key,
+/// so the PSET can be finalized once the witness data is known.
+pub fn update_input(
+    pset: &mut PartiallySignedTransaction,
+    index: u32,
+    program_bytes: Vec<u8>,
+    internal_key: XOnlyPublicKey,
+    control_block: Option<ControlBlock>,
+    witness_utxo: Option<elements::TxOut>,
+) -> Result<(), Error> {
+    let cmr = decode::decode_program_bytes::<Elements>(&program_bytes)
+        .with_argument("program")?
+        .cmr();
+    let input = pset_input(pset, index)?;
+    let leaf_script = address::simplicity_leaf_script(cmr);
+
+    let (control_block, merkle_root) = match control_block {
+        Some(control_block) => {
+            if control_block.leaf_version != util::simplicity_leaf_version() {
+                return Err(Error::ControlBlockLeafVersion(
+                    control_block.leaf_version.as_u8(),
+                ));
+            }
+            let leaf_hash = TapLeafHash::from_script(&leaf_script, control_block.leaf_version);
+            let merkle_root =
+                script::taproot_merkle_root(leaf_hash, control_block.merkle_branch.as_inner());
+            (control_block, Some(merkle_root))
+        }
+        None => {
+            let spend_info = address::simplicity_spend_info(cmr, internal_key, &[])?;
+            let control_block = address::simplicity_control_block(&spend_info, cmr)?;
+            (control_block, spend_info.merkle_root())
+        }
+    };
+
+    // Both given and generated control blocks must be able to spend the output, if it is known
+    let output_key = witness_utxo
+        .as_ref()
+        .or(input.witness_utxo.as_ref())
+        .and_then(|utxo| script::taproot_output_key(&utxo.script_pubkey));
+    if let Some(output_key) = output_key {
+        let secp = secp256k1_zkp::Secp256k1::verification_only();
+        if !control_block.verify_taproot_commitment(&secp, &output_key, &leaf_script) {
+            return Err(Error::ControlBlockCommitment);
+        }
+    }
+
+    if witness_utxo.is_some() {
+        input.witness_utxo = witness_utxo;
+    }
+    input.tap_merkle_root = merkle_root;
+    input.tap_internal_key = Some(control_block.internal_key);
+    input.tap_scripts.insert(
+        control_block,
+        (leaf_script, util::simplicity_leaf_version()),
+    );
+    input
+        .proprietary
+        .insert(program_key(cmr.as_ref()), program_bytes);
+    Ok(())
+}
+
+/// Return whether the given input carries a Simplicity program.
+pub fn has_program(input: &elements::pset::Input) -> bool {
+    input
+        .proprietary
+        .keys()
+        .any(|key| key.prefix == PROPRIETARY_PREFIX && key.subtype == PROPRIETARY_PROGRAM)
+}
+
+/// Finalize the given input of the PSET by assembling the witness stack of its Simplicity spend.
+///
+/// If no witness values are given, then the stored program must be followed by its witness data.
+/// The Simplicity fields of the input are removed afterwards, as required of finalizers.
+pub fn finalize_input(
+    pset: &mut PartiallySignedTransaction,
+    index: u32,
+    witness_values: Option<&[String]>,
+) -> Result<(), Error> {
+    let input = pset_input(pset, index)?;
+
+    let (control_block, program_bytes) = input
+        .tap_scripts
+        .iter()
+        .filter(|(_, (_, version))| *version == util::simplicity_leaf_version())
+        .find_map(|(control_block, (script, _))| {
+            let program_bytes = input.proprietary.get(&program_key(script.as_bytes()))?;
+            Some((control_block.clone(), program_bytes.clone()))
+        })
+        .ok_or(Error::MissingProgram(index))?;
+
+    let program = match witness_values {
+        Some(values) => {
+            let program = decode::decode_program_bytes::<Elements>(&program_bytes)
+                .with_argument("program")?;
+            witness::finalize_with_hex(&program, values).with_argument("witness")?
+        }
+        None => decode::decode_finalized_program_bytes::<Elements>(&program_bytes)
+            .with_argument("program")?,
+    };
+
+    input.final_script_witness = Some(create::simplicity_witness_stack(&program, &control_block)?);
+    input.tap_scripts.clear();
+    input.tap_script_sigs.clear();
+    input.tap_key_origins.clear();
+    input.tap_internal_key = None;
+    input.tap_merkle_root = None;
+    input
+        .proprietary
+        .retain(|key, _| key.prefix != PROPRIETARY_PREFIX);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn unsigned_tx() -> elements::Transaction {
+        elements::Transaction {
+            version: 2,
+            lock_time: elements::LockTime::ZERO,
+            input: vec![elements::TxIn::default()],
+            output: vec![],
+        }
+    }
+
+    fn taproot_utxo(spend_info: &elements::taproot::TaprootSpendInfo) -> elements::TxOut {
+        let address = elements::Address::p2tr_tweaked(
+            spend_info.output_key(),
+            None,
+            Network::ElementsRegtest.address_params(),
+        );
+        elements::TxOut {
+            script_pubkey: address.script_pubkey(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn update_then_finalize() {
+        // The program `unit` without witness data
+        let program_bytes = vec![0x24];
+        let cmr = decode::decode_program_bytes::<Elements>(&program_bytes)
+            .unwrap()
+            .cmr();
+        let internal_key = address::unspendable_internal_key();
+        let spend_info = address::simplicity_spend_info(cmr, internal_key, &[]).unwrap();
+        let utxo = taproot_utxo(&spend_info);
+
+        let mut pset = PartiallySignedTransaction::from_tx(unsigned_tx());
+        update_input(
+            &mut pset,
+            0,
+            program_bytes.clone(),
+            internal_key,
+            None,
+            Some(utxo.clone()),
+        )
+        .unwrap();
+        assert!(has_program(&pset.inputs()[0]));
+        finalize_input(&mut pset, 0, None).unwrap();
+
+        let input = &pset.inputs()[0];
+        assert!(!has_program(input));
+        assert!(input.tap_scripts.is_empty());
+        let stack = input.final_script_witness.as_ref().unwrap();
+        let spend = ScriptSpendWitness::new(stack).unwrap();
+        assert!(spend.is_simplicity_leaf());
+        assert_eq!(spend.script_inputs, [program_bytes]);
+        assert_eq!(spend.script, cmr.as_ref());
+        assert!(spend.commits_to_output(&utxo.script_pubkey));
+        let simplicity = spend
+            .get_simplicity_spend_info(Network::ElementsRegtest)
+            .unwrap();
+        assert!(simplicity.error.is_none());
+    }
+
+    #[test]
+    fn update_with_other_output() {
+        let program_bytes = vec![0x24];
+        let cmr = decode::decode_program_bytes::<Elements>(&program_bytes)
+            .unwrap()
+            .cmr();
+        let other_key = XOnlyPublicKey::from_slice(&[0x01; 32]).unwrap();
+        let spend_info = address::simplicity_spend_info(cmr, other_key, &[]).unwrap();
+
+        let mut pset = PartiallySignedTransaction::from_tx(unsigned_tx());
+        let result = update_input(
+            &mut pset,
+            0,
+            program_bytes,
+            address::unspendable_internal_key(),
+            None,
+            Some(taproot_utxo(&spend_info)),
+        );
+        assert!(matches!(result, Err(Error::ControlBlockCommitment)));
+        assert!(!has_program(&pset.inputs()[0]));
+    }
+}
@@ -0,0 +1,600 @@
+use elements::hashes::{sha256, Hash, HashEngine};
+use elements::hex::ToHex;
+use elements::opcodes;
+use elements::opcodes::all::{
+    OP_CHECKMULTISIG, OP_CHECKSIG, OP_CHECKSIGADD, OP_CHECKSIGVERIFY, OP_CLTV, OP_CSV, OP_DUP,
+    OP_EQUAL, OP_EQUALVERIFY, OP_HASH160, OP_HASH256, OP_NUMEQUAL, OP_NUMEQUALVERIFY,
+    OP_PUSHNUM_NEG1, OP_RETURN, OP_RIPEMD160, OP_SHA256, OP_SIZE, OP_VERIFY,
+};
+use elements::script::Instruction;
+use elements::secp256k1_zkp::XOnlyPublicKey;
+use elements::taproot::{TapBranchHash, TapLeafHash};
+use serde::{Deserialize, Serialize};
+use simplicity::elements;
+
+/// Version and program of a segwit output.
+#[derive(Clone, PartialEq, Eq, Debug, Deserialize, Serialize)]
+#[non_exhaustive]
+pub struct WitnessProgramInfo {
+    pub version: u8,
+    pub program: String,
+}
+
+/// Return the witness version and program of the given script, if it is a segwit output.
+pub fn witness_program(script: &elements::Script) -> Option<(u8, &[u8])> {
+    let bytes = script.as_bytes();
+    if bytes.len() < 4 || bytes.len() > 42 || bytes[1] as usize != bytes.len() - 2 {
+        return None;
+    }
+    let version = match bytes[0] {
+        0x00 => 0,
+        0x51..=0x60 => bytes[0] - 0x50,
+        _ => return None,
+    };
+    Some((version, &bytes[2..]))
+}
+
+/// Return the description of the witness program of the given script, if any.
+pub fn witness_program_info(script: &elements::Script) -> Option<WitnessProgramInfo> {
+    witness_program(script).map(|(version, program)| WitnessProgramInfo {
+        version,
+        program: program.to_hex(),
+    })
+}
+
+/// Return the type of a P2SH redeem script that wraps the given witness program.
+pub fn wrapped_segwit_type(version: u8, program: &[u8]) -> &'static str {
+    match (version, program.len()) {
+        (0, 20) => "p2sh-p2wpkh",
+        (0, 32) => "p2sh-p2wsh",
+        _ => "p2sh-witness",
+    }
+}
+
+/// Return the witness program that is wrapped in the given P2SH scriptSig, if any.
+///
+/// The scriptSig of a wrapped segwit input pushes the redeem script, which is a witness program.
+pub fn wrapped_witness_program(script_sig: &elements::Script) -> Option<elements::Script> {
+    let mut instructions = script_sig.instructions();
+    let redeem_script = match (instructions.next(), instructions.next()) {
+        (Some(Ok(Instruction::PushBytes(bytes))), None) => elements::Script::from(bytes.to_vec()),
+        _ => return None,
+    };
+    witness_program(&redeem_script)?;
+    Some(redeem_script)
+}
+
+/// Threshold and keys of a bare multisig output.
+#[derive(Clone, PartialEq, Eq, Debug, Deserialize, Serialize)]
+#[non_exhaustive]
+pub struct MultisigInfo {
+    pub required: u8,
+    pub pubkeys: Vec<String>,
+}
+
+/// Return the small number that is pushed by the given opcode, if any.
+fn small_number(instruction: &Instruction) -> Option<u8> {
+    match instruction {
+        Instruction::Op(op) if (0x51..=0x60).contains(&op.into_u8()) => Some(op.into_u8() - 0x50),
+        _ => None,
+    }
+}
+
+/// Return the threshold and keys of the given script, if it is `m <keys> n CHECKMULTISIG`.
+pub fn multisig_info(script: &elements::Script) -> Option<MultisigInfo> {
+    let instructions: Vec<_> = script.instructions().collect::<Result<_, _>>().ok()?;
+    let (last, rest) = instructions.split_last()?;
+    match last {
+        Instruction::Op(op) if *op == OP_CHECKMULTISIG && rest.len() >= 3 => {}
+        _ => return None,
+    }
+    let required = small_number(&rest[0])?;
+    let total = small_number(&rest[rest.len() - 1])?;
+    let pubkeys = rest[1..rest.len() - 1]
+        .iter()
+        .map(|instruction| match instruction {
+            Instruction::PushBytes(bytes) if bytes.len() == 33 || bytes.len() == 65 => {
+                Some(bytes.to_hex())
+            }
+            _ => None,
+        })
+        .collect::<Option<Vec<_>>>()?;
+
+    if required == 0 || required > total || pubkeys.len() != total as usize {
+        return None;
+    }
+    Some(MultisigInfo { required, pubkeys })
+}
+
+/// Payload of an `OP_RETURN` output.
+#[derive(Clone, PartialEq, Eq, Debug, Deserialize, Serialize)]
+#[non_exhaustive]
+pub struct OpReturnInfo {
+    /// Hex encoding of the concatenated data pushes
+    pub data: String,
+    /// The data as text, if it is valid UTF-8
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub utf8: Option<String>,
+}
+
+/// Return the payload of the given script, if it is an `OP_RETURN` output with data pushes.
+pub fn op_return_info(script: &elements::Script) -> Option<OpReturnInfo> {
+    let mut instructions = script.instructions();
+    match instructions.next() {
+        Some(Ok(Instruction::Op(op))) if op == OP_RETURN => {}
+        _ => return None,
+    }
+    let mut data = vec![];
+    for instruction in instructions {
+        match instruction {
+            Ok(Instruction::PushBytes(bytes)) => data.extend_from_slice(bytes),
+            _ => return None,
+        }
+    }
+
+    Some(OpReturnInfo {
+        data: data.to_hex(),
+        utf8: String::from_utf8(data)
+            .ok()
+            .filter(|text| !text.is_empty() && !text.chars().any(char::is_control)),
+    })
+}
+
+/// Return the x-only output key of the given script, if it is a P2TR output.
+pub fn taproot_output_key(script_pubkey: &elements::Script) -> Option<XOnlyPublicKey> {
+    if !script_pubkey.is_v1_p2tr() {
+        return None;
+    }
+    XOnlyPublicKey::from_slice(&script_pubkey.as_bytes()[2..]).ok()
+}
+
+/// Return the taproot merkle root that is obtained by hashing the given leaf along the given path.
+pub fn taproot_merkle_root(leaf_hash: TapLeafHash, merkle_path: &[sha256::Hash]) -> TapBranchHash {
+    let mut node = sha256::Hash::from_byte_array(leaf_hash.to_byte_array());
+    for sibling in merkle_path {
+        let mut engine = TapBranchHash::engine();
+        if node < *sibling {
+            engine.input(&node[..]);
+            engine.input(&sibling[..]);
+        } else {
+            engine.input(&sibling[..]);
+            engine.input(&node[..]);
+        }
+        node = sha256::Hash::from_byte_array(TapBranchHash::from_engine(engine).to_byte_array());
+    }
+    TapBranchHash::from_byte_array(node.to_byte_array())
+}
+
+fn is_op(instruction: &Instruction, opcode: opcodes::All) -> bool {
+    matches!(instruction, Instruction::Op(op) if *op == opcode)
+}
+
+fn push_bytes<'a>(instruction: &Instruction<'a>) -> Option<&'a [u8]> {
+    match instruction {
+        Instruction::PushBytes(bytes) => Some(bytes),
+        _ => None,
+    }
+}
+
+/// Return the number that is pushed by the given instruction, if any.
+fn push_number(instruction: &Instruction) -> Option<i64> {
+    if let Some(n) = small_number(instruction) {
+        return Some(n.into());
+    }
+    if is_op(instruction, OP_PUSHNUM_NEG1) {
+        return Some(-1);
+    }
+    let bytes = push_bytes(instruction)?;
+    if bytes.len() > 5 {
+        return None;
+    }
+    let mut n: i64 = 0;
+    for (i, byte) in bytes.iter().enumerate() {
+        n |= i64::from(*byte) << (8 * i);
+    }
+    match bytes.last() {
+        Some(last) if last & 0x80 != 0 => Some(-(n & !(0x80 << (8 * (bytes.len() - 1))))),
+        _ => Some(n),
+    }
+}
+
+/// Return the fragment with the given name if it is completed by `opcode`,
+/// or its `v:` wrapper if it is completed by `verify_opcode`.
+fn complete(
+    ins: &[Instruction],
+    name: String,
+    consumed: usize,
+    opcode: opcodes::All,
+    verify_opcode: opcodes::All,
+) -> Option<(String, usize)> {
+    match ins.get(consumed - 1)? {
+        last if is_op(last, opcode) => Some((name, consumed)),
+        last if is_op(last, verify_opcode) => Some((format!("v:{}", name), consumed)),
+        _ => None,
+    }
+}
+
+/// Return the Miniscript fragment at the start of the given instructions,
+/// together with the number of consumed instructions.
+///
+/// Fragments that end with a `VERIFY` opcode are wrapped in `v:`.
+fn fragment(ins: &[Instruction]) -> Option<(String, usize)> {
+    // multi_a(k,K1,...,Kn): <K1> CHECKSIG <K2> CHECKSIGADD ... <Kn> CHECKSIGADD <k> NUMEQUAL
+    if ins.len() >= 6
+        && push_bytes(&ins[0]).map(<[u8]>::len) == Some(32)
+        && is_op(&ins[1], OP_CHECKSIG)
+    {
+        let mut keys = vec![push_bytes(&ins[0])?.to_hex()];
+        let mut i = 2;
+        while i + 1 < ins.len() && is_op(&ins[i + 1], OP_CHECKSIGADD) {
+            keys.push(push_bytes(&ins[i]).filter(|key| key.len() == 32)?.to_hex());
+            i += 2;
+        }
+        if keys.len() > 1 {
+            let k = push_number(ins.get(i)?)?;
+            let name = format!("multi_a({},{})", k, keys.join(","));
+            return complete(ins, name, i + 2, OP_NUMEQUAL, OP_NUMEQUALVERIFY);
+        }
+    }
+
+    // pk(K): <K> CHECKSIG
+    if let Some(key) = ins
+        .first()
+        .and_then(push_bytes)
+        .filter(|key| key.len() == 32)
+    {
+        return complete(
+            ins,
+            format!("pk({})", key.to_hex()),
+            2,
+            OP_CHECKSIG,
+            OP_CHECKSIGVERIFY,
+        );
+    }
+
+    // pk_h(H): DUP HASH160 <H> EQUALVERIFY CHECKSIG
+    if ins.len() >= 5
+        && is_op(&ins[0], OP_DUP)
+        && is_op(&ins[1], OP_HASH160)
+        && is_op(&ins[3], OP_EQUALVERIFY)
+    {
+        let hash = push_bytes(&ins[2]).filter(|hash| hash.len() == 20)?;
+        return complete(
+            ins,
+            format!("pk_h({})", hash.to_hex()),
+            5,
+            OP_CHECKSIG,
+            OP_CHECKSIGVERIFY,
+        );
+    }
+
+    // older(n) and after(n): <n> CSV or <n> CLTV, optionally followed by VERIFY
+    let timelock = match ins.get(1) {
+        Some(op) if is_op(op, OP_CSV) => Some("older"),
+        Some(op) if is_op(op, OP_CLTV) => Some("after"),
+        _ => None,
+    };
+    if let Some(timelock) = timelock {
+        let name = format!("{}({})", timelock, push_number(&ins[0])?);
+        return match ins.get(2) {
+            Some(next) if is_op(next, OP_VERIFY) => Some((format!("v:{}", name), 3)),
+            _ => Some((name, 2)),
+        };
+    }
+
+    // sha256(h) and friends: SIZE <32> EQUALVERIFY <HASH> <h> EQUAL
+    if ins.len() >= 6
+        && is_op(&ins[0], OP_SIZE)
+        && push_number(&ins[1]) == Some(32)
+        && is_op(&ins[2], OP_EQUALVERIFY)
+    {
+        let (name, len) = match &ins[3] {
+            op if is_op(op, OP_SHA256) => ("sha256", 32),
+            op if is_op(op, OP_HASH256) => ("hash256", 32),
+            op if is_op(op, OP_RIPEMD160) => ("ripemd160", 20),
+            op if is_op(op, OP_HASH160) => ("hash160", 20),
+            _ => return None,
+        };
+        let hash = push_bytes(&ins[4]).filter(|hash| hash.len() == len)?;
+        return complete(
+            ins,
+            format!("{}({})", name, hash.to_hex()),
+            6,
+            OP_EQUAL,
+            OP_EQUALVERIFY,
+        );
+    }
+
+    None
+}
+
+/// Recognise the given tapscript as a sequence of common Miniscript fragments.
+///
+/// Scripts of the form `v:X Y` are rendered as `and_v(v:X,Y)`.
+/// Scripts that contain unknown fragments are not recognised.
+pub fn miniscript_fragments(script: &elements::Script) -> Option<String> {
+    let ins: Vec<_> = script.instructions().collect::<Result<_, _>>().ok()?;
+    let mut fragments = vec![];
+    let mut i = 0;
+    while i < ins.len() {
+        let (name, consumed) = fragment(&ins[i..])?;
+        fragments.push(name);
+        i += consumed;
+    }
+
+    let (last, init) = fragments.split_last()?;
+    if last.starts_with("v:") || init.iter().any(|name| !name.starts_with("v:")) {
+        return None;
+    }
+    Some(
+        init.iter()
+            .rev()
+            .fold(last.clone(), |acc, name| format!("and_v({},{})", name, acc)),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use elements::script::Builder;
+
+    use super::*;
+
+    const KEY_A: [u8; 32] = [0x02; 32];
+    const KEY_B: [u8; 32] = [0x03; 32];
+    const KEY_C: [u8; 32] = [0x04; 32];
+
+    fn instructions(script: &elements::Script) -> Vec<Instruction> {
+        script.instructions().collect::<Result<_, _>>().unwrap()
+    }
+
+    #[test]
+    fn push_number_values() {
+        let numbers = [
+            -1_000_000,
+            -256,
+            -128,
+            -1,
+            0,
+            1,
+            16,
+            17,
+            127,
+            128,
+            255,
+            256,
+            65_535,
+            1_000_000,
+            i64::from(u32::MAX),
+        ];
+        for n in numbers {
+            let script = Builder::new().push_int(n).into_script();
+            assert_eq!(push_number(&instructions(&script)[0]), Some(n), "{}", n);
+        }
+
+        let script = Builder::new()
+            .push_slice(&[0xff; 6])
+            .push_opcode(OP_CHECKSIG)
+            .into_script();
+        let ins = instructions(&script);
+        assert_eq!(push_number(&ins[0]), None);
+        assert_eq!(push_number(&ins[1]), None);
+    }
+
+    #[test]
+    fn fragment_keys() {
+        let script = Builder::new()
+            .push_slice(&KEY_A)
+            .push_opcode(OP_CHECKSIGVERIFY)
+            .into_script();
+        let expected = format!("v:pk({})", KEY_A.to_hex());
+        assert_eq!(fragment(&instructions(&script)), Some((expected, 2)));
+
+        let script = Builder::new()
+            .push_opcode(OP_DUP)
+            .push_opcode(OP_HASH160)
+            .push_slice(&[0x11; 20])
+            .push_opcode(OP_EQUALVERIFY)
+            .push_opcode(OP_CHECKSIG)
+            .into_script();
+        let expected = format!("pk_h({})", [0x11u8; 20].to_hex());
+        assert_eq!(fragment(&instructions(&script)), Some((expected, 5)));
+
+        let script = Builder::new()
+            .push_slice(&KEY_A)
+            .push_opcode(OP_CHECKSIG)
+            .push_slice(&KEY_B)
+            .push_opcode(OP_CHECKSIGADD)
+            .push_slice(&KEY_C)
+            .push_opcode(OP_CHECKSIGADD)
+            .push_int(2)
+            .push_opcode(OP_NUMEQUAL)
+            .into_script();
+        let expected = format!(
+            "multi_a(2,{},{},{})",
+            KEY_A.to_hex(),
+            KEY_B.to_hex(),
+            KEY_C.to_hex()
+        );
+        assert_eq!(fragment(&instructions(&script)), Some((expected, 8)));
+    }
+
+    #[test]
+    fn fragment_timelocks_and_hashes() {
+        let script = Builder::new()
+            .push_int(144)
+            .push_opcode(OP_CSV)
+            .into_script();
+        let expected = ("older(144)".to_owned(), 2);
+        assert_eq!(fragment(&instructions(&script)), Some(expected));
+
+        let script = Builder::new()
+            .push_int(500_000)
+            .push_opcode(OP_CLTV)
+            .push_opcode(OP_VERIFY)
+            .into_script();
+        let expected = ("v:after(500000)".to_owned(), 3);
+        assert_eq!(fragment(&instructions(&script)), Some(expected));
+
+        let script = Builder::new()
+            .push_opcode(OP_SIZE)
+            .push_int(32)
+            .push_opcode(OP_EQUALVERIFY)
+            .push_opcode(OP_SHA256)
+            .push_slice(&[0x22; 32])
+            .push_opcode(OP_EQUAL)
+            .into_script();
+        let expected = format!("sha256({})", [0x22u8; 32].to_hex());
+        assert_eq!(fragment(&instructions(&script)), Some((expected, 6)));
+
+        let script = Builder::new()
+            .push_opcode(OP_SIZE)
+            .push_int(32)
+            .push_opcode(OP_EQUALVERIFY)
+            .push_opcode(OP_SHA256)
+            .push_slice(&[0x22; 20])
+            .push_opcode(OP_EQUAL)
+            .into_script();
+        assert_eq!(fragment(&instructions(&script)), None);
+
+        let script = Builder::new().push_opcode(OP_RETURN).into_script();
+        assert_eq!(fragment(&instructions(&script)), None);
+    }
+
+    #[test]
+    fn miniscript_fragments_and_v() {
+        let script = Builder::new()
+            .push_int(144)
+            .push_opcode(OP_CSV)
+            .push_opcode(OP_VERIFY)
+            .push_slice(&KEY_A)
+            .push_opcode(OP_CHECKSIGVERIFY)
+            .push_slice(&KEY_B)
+            .push_opcode(OP_CHECKSIG)
+            .into_script();
+        let expected = format!(
+            "and_v(v:older(144),and_v(v:pk({}),pk({})))",
+            KEY_A.to_hex(),
+            KEY_B.to_hex()
+        );
+        assert_eq!(miniscript_fragments(&script), Some(expected));
+
+        let script = Builder::new()
+            .push_slice(&KEY_A)
+            .push_opcode(OP_CHECKSIG)
+            .into_script();
+        let expected = format!("pk({})", KEY_A.to_hex());
+        assert_eq!(miniscript_fragments(&script), Some(expected));
+    }
+
+    #[test]
+    fn miniscript_fragments_unrecognised() {
+        // Last fragment must not be wrapped in `v:`
+        let script = Builder::new()
+            .push_slice(&KEY_A)
+            .push_opcode(OP_CHECKSIGVERIFY)
+            .into_script();
+        assert_eq!(miniscript_fragments(&script), None);
+
+        // Earlier fragments must be wrapped in `v:`
+        let script = Builder::new()
+            .push_slice(&KEY_A)
+            .push_opcode(OP_CHECKSIG)
+            .push_slice(&KEY_B)
+            .push_opcode(OP_CHECKSIG)
+            .into_script();
+        assert_eq!(miniscript_fragments(&script), None);
+
+        // Unknown fragments are not recognised
+        let script = Builder::new()
+            .push_slice(&KEY_A)
+            .push_opcode(OP_CHECKSIGVERIFY)
+            .push_opcode(OP_RETURN)
+            .into_script();
+        assert_eq!(miniscript_fragments(&script), None);
+
+        assert_eq!(miniscript_fragments(&elements::Script::new()), None);
+    }
+
+    #[test]
+    fn witness_programs() {
+        let script = elements::Script::from([&[0x00, 0x14][..], &[0x11; 20][..]].concat());
+        assert_eq!(witness_program(&script), Some((0, &[0x11; 20][..])));
+        let script = elements::Script::from([&[0x51, 0x20][..], &KEY_A[..]].concat());
+        assert_eq!(witness_program(&script), Some((1, &KEY_A[..])));
+        let script = elements::Script::from(vec![0x60, 0x02, 0xaa, 0xbb]);
+        assert_eq!(witness_program(&script), Some((16, &[0xaa, 0xbb][..])));
+
+        // Push length does not match the program
+        let script = elements::Script::from([&[0x00, 0x14][..], &[0x11; 19][..]].concat());
+        assert_eq!(witness_program(&script), None);
+        // Programs must have between 2 and 40 bytes
+        let script = elements::Script::from(vec![0x51, 0x01, 0xaa]);
+        assert_eq!(witness_program(&script), None);
+        let script = elements::Script::from([&[0x51, 0x29][..], &[0x11; 41][..]].concat());
+        assert_eq!(witness_program(&script), None);
+        // OP_1NEGATE is not a witness version
+        let script = elements::Script::from(vec![0x4f, 0x02, 0xaa, 0xbb]);
+        assert_eq!(witness_program(&script), None);
+    }
+
+    #[test]
+    fn multisig() {
+        let key_a = [0x02; 33];
+        let key_b = [0x03; 33];
+        let multisig = |required: i64, keys: &[&[u8]], total: i64| {
+            let mut builder = Builder::new().push_int(required);
+            for key in keys {
+                builder = builder.push_slice(key);
+            }
+            let script = builder
+                .push_int(total)
+                .push_opcode(OP_CHECKMULTISIG)
+                .into_script();
+            multisig_info(&script)
+        };
+
+        let info = multisig(1, &[&key_a, &key_b], 2).unwrap();
+        assert_eq!(info.required, 1);
+        assert_eq!(info.pubkeys, vec![key_a.to_hex(), key_b.to_hex()]);
+        assert!(multisig(1, &[&[0x04; 65]], 1).is_some());
+
+        // More required signatures than keys
+        assert_eq!(multisig(3, &[&key_a, &key_b], 2), None);
+        // No required signatures
+        assert_eq!(multisig(0, &[&key_a, &key_b], 2), None);
+        // Key count does not match the total
+        assert_eq!(multisig(1, &[&key_a, &key_b], 3), None);
+        // Keys must be 33 or 65 bytes
+        assert_eq!(multisig(1, &[&KEY_A, &KEY_B], 2), None);
+    }
+
+    #[test]
+    fn op_return() {
+        let script = Builder::new().push_opcode(OP_RETURN).into_script();
+        let info = op_return_info(&script).unwrap();
+        assert_eq!(info.data, "");
+        assert_eq!(info.utf8, None);
+
+        let script = Builder::new()
+            .push_opcode(OP_RETURN)
+            .push_slice(b"hello")
+            .push_slice(b" world")
+            .into_script();
+        let info = op_return_info(&script).unwrap();
+        assert_eq!(info.data, b"hello world".to_hex());
+        assert_eq!(info.utf8.as_deref(), Some("hello world"));
+
+        // Only data pushes may follow OP_RETURN
+        let script = Builder::new()
+            .push_opcode(OP_RETURN)
+            .push_opcode(OP_CHECKSIG)
+            .into_script();
+        assert_eq!(op_return_info(&script), None);
+        // OP_RETURN must come first
+        let script = Builder::new()
+            .push_slice(b"hello")
+            .push_opcode(OP_RETURN)
+            .into_script();
+        assert_eq!(op_return_info(&script), None);
+    }
+}
@@ -0,0 +1,68 @@
+use std::iter;
+
+use elements::hex::ToHex;
+use serde::{Deserialize, Serialize};
+use simplicity::elements;
+use simplicity::jet::elements::ElementsEnv;
+use simplicity::jet::Elements;
+use simplicity::node::ConstructNode;
+use simplicity::BitMachine;
+
+use crate::error::Error;
+
+#[derive(Clone, PartialEq, Eq, Debug, Deserialize, Serialize)]
+#[non_exhaustive]
+pub struct SighashInfo {
+    pub sig_all_hash: String,
+    pub tx_hash: String,
+    pub tap_env_hash: String,
+    pub inputs_hash: String,
+    pub outputs_hash: String,
+    pub issuances_hash: String,
+    pub tapleaf_hash: String,
+    pub genesis_hash: String,
+}
+
+/// Evaluate the given hash jet in the given environment.
+///
+/// The jet must take unit as input and return a 256-bit hash.
+pub fn jet_hash(jet: Elements, env: &ElementsEnv) -> Result<[u8; 32], Error> {
+    let program = ConstructNode::jet(jet)
+        .finalize_types_non_program()
+        .map_err(simplicity::Error::from)?
+        .finalize(iter::empty())?;
+    let mut mac = BitMachine::for_program(&program);
+    let output = mac
+        .exec(&program, env)
+        .map_err(|e| Error::Execution(e.to_string()))?;
+
+    let (bytes, bit_len) = output.to_bytes_len();
+    if bit_len != 256 {
+        return Err(Error::Execution(format!(
+            "jet {} returned {} bits instead of a hash",
+            jet, bit_len
+        )));
+    }
+    let mut hash = [0; 32];
+    hash.copy_from_slice(&bytes);
+    Ok(hash)
+}
+
+/// Compute the message that is signed by `sig_all_hash`-based Simplicity programs.
+pub fn sig_all_hash(env: &ElementsEnv) -> Result<[u8; 32], Error> {
+    jet_hash(Elements::SigAllHash, env)
+}
+
+/// Compute `sig_all_hash` and its component hashes in the given environment.
+pub fn sighash_info(env: &ElementsEnv) -> Result<SighashInfo, Error> {
+    Ok(SighashInfo {
+        sig_all_hash: sig_all_hash(env)?.to_hex(),
+        tx_hash: jet_hash(Elements::TxHash, env)?.to_hex(),
+        tap_env_hash: jet_hash(Elements::TapEnvHash, env)?.to_hex(),
+        inputs_hash: jet_hash(Elements::InputsHash, env)?.to_hex(),
+        outputs_hash: jet_hash(Elements::OutputsHash, env)?.to_hex(),
+        issuances_hash: jet_hash(Elements::IssuancesHash, env)?.to_hex(),
+        tapleaf_hash: jet_hash(Elements::TapleafHash, env)?.to_hex(),
+        genesis_hash: jet_hash(Elements::GenesisBlockHash, env)?.to_hex(),
+    })
+}
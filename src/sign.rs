@@ -0,0 +1,88 @@
+use elements::hex::{FromHex, ToHex};
+use elements::secp256k1_zkp::{self, KeyPair, Message};
+use serde::{Deserialize, Serialize};
+use simplicity::jet::Elements;
+use simplicity::{elements, CommitNode};
+
+use crate::create;
+use crate::error::Error;
+use crate::witness::{self, FinalizedProgramInfo};
+
+#[derive(Clone, PartialEq, Eq, Debug, Deserialize, Serialize)]
+#[non_exhaustive]
+pub struct SignInfo {
+    pub message: String,
+    pub public_key: String,
+    pub signature: String,
+    pub program: FinalizedProgramInfo,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub witness_stack: Option<Vec<String>>,
+}
+
+/// Sign the given message with the given key pair and splice the signature into the program.
+///
+/// The signature is placed in the witness node with the given index.
+/// The other witness nodes are populated with the given hex values;
+/// the value at the signature index is ignored.
+///
+/// The signature uses auxiliary randomness from the operating system, as recommended by BIP 340.
+///
+/// If a control block is given, then the full taproot witness stack of the spend is returned.
+pub fn sign_program(
+    program: &CommitNode<Elements>,
+    keypair: &KeyPair,
+    message: [u8; 32],
+    witness_index: usize,
+    values: &[Option<String>],
+    control_block: Option<&elements::taproot::ControlBlock>,
+) -> Result<SignInfo, Error> {
+    let secp = secp256k1_zkp::Secp256k1::signing_only();
+    let msg = Message::from_slice(&message)?;
+    let mut aux_rand = [0u8; 32];
+    getrandom::getrandom(&mut aux_rand)?;
+    let signature = secp.sign_schnorr_with_aux_rand(&msg, keypair, &aux_rand);
+
+    let n_slots = witness::witness_types(program).len();
+    if witness_index >= n_slots {
+        return Err(Error::WitnessIndex(witness_index, n_slots));
+    }
+    let values = (0..n_slots)
+        .map(|index| {
+            if index == witness_index {
+                Ok(signature.as_ref().to_hex())
+            } else {
+                values
+                    .get(index)
+                    .cloned()
+                    .flatten()
+                    .ok_or(Error::MissingWitnessValue(index))
+            }
+        })
+        .collect::<Result<Vec<_>, Error>>()?;
+    let program = witness::finalize_with_hex(program, &values)?;
+    let program_info = witness::finalized_program_info(program.as_ref())?;
+
+    let witness_stack = control_block
+        .map(|control_block| create::simplicity_witness_stack(program.as_ref(), control_block))
+        .transpose()?
+        .map(|stack| stack.iter().map(|item| item.to_hex()).collect());
+
+    Ok(SignInfo {
+        message: message.to_hex(),
+        public_key: keypair.x_only_public_key().0.to_hex(),
+        signature: signature.as_ref().to_hex(),
+        program: program_info,
+        witness_stack,
+    })
+}
+
+/// Parse a 32-byte message from the given hex string.
+pub fn parse_message(hex: &str) -> Result<[u8; 32], Error> {
+    let bytes = Vec::<u8>::from_hex(hex)?;
+    let mut message = [0; 32];
+    if bytes.len() != message.len() {
+        return Err(Error::MessageLength(bytes.len()));
+    }
+    message.copy_from_slice(&bytes);
+    Ok(message)
+}
@@ -1,25 +1,36 @@
-use std::io::Write;
+use std::str::FromStr;
 
-use elements::confidential;
-use elements::hex::ToHex;
+use base64::engine::general_purpose;
+use base64::Engine;
+use elements::hex::{FromHex, ToHex};
+use elements::secp256k1_zkp::{self, RangeProof, SurjectionProof, Tweak};
+use elements::taproot::{LeafVersion, TapBranchHash, TapLeafHash};
+use elements::{confidential, encode};
 use serde::{Deserialize, Serialize};
 use simplicity::elements;
+use simplicity::jet::elements::ElementsUtxo;
+use simplicity::jet::Elements;
 
-use crate::encode;
-use crate::util::{GetInfo, Network};
+use crate::decode;
+use crate::error::{Error, ErrorContext};
+use crate::peg::{self, PeginInfo, PegoutInfo};
+use crate::prog::{self, ProgramInfo};
+use crate::script::{self, MultisigInfo, OpReturnInfo, WitnessProgramInfo};
+use crate::util::{self, GetInfo, Network};
 
 #[derive(Clone, PartialEq, Eq, Debug, Deserialize, Serialize)]
+#[non_exhaustive]
 pub struct TransactionInfo {
-    txid: elements::Txid,
-    wtxid: elements::Wtxid,
-    hash: elements::Wtxid,
-    size: usize,
-    weight: usize,
-    vsize: usize,
-    version: u32,
-    locktime: elements::LockTime,
-    inputs: Vec<InputInfo>,
-    outputs: Vec<OutputInfo>,
+    pub txid: elements::Txid,
+    pub wtxid: elements::Wtxid,
+    pub hash: elements::Wtxid,
+    pub size: usize,
+    pub weight: usize,
+    pub vsize: usize,
+    pub version: u32,
+    pub locktime: elements::LockTime,
+    pub inputs: Vec<InputInfo>,
+    pub outputs: Vec<OutputInfo>,
 }
 
 impl GetInfo<TransactionInfo> for elements::Transaction {
@@ -39,27 +50,195 @@ impl GetInfo<TransactionInfo> for elements::Transaction {
     }
 }
 
+impl TransactionInfo {
+    /// Check the script spends of the given transaction against the outputs that they spend.
+    ///
+    /// The description must have been obtained from the same transaction.
+    pub fn check_spent_outputs(
+        &mut self,
+        tx: &elements::Transaction,
+        utxos: &[ElementsUtxo],
+    ) -> Result<(), Error> {
+        if utxos.len() != tx.input.len() {
+            return Err(Error::UtxoCount(utxos.len(), tx.input.len()));
+        }
+
+        for ((info, txin), utxo) in self.inputs.iter_mut().zip(&tx.input).zip(utxos) {
+            let spend = ScriptSpendWitness::new(&txin.witness.script_witness);
+            if let (Some(spend_info), Some(spend)) = (info.witness.script_spend.as_mut(), spend) {
+                spend_info.commits_to_output_key =
+                    Some(spend.commits_to_output(&utxo.script_pubkey));
+            }
+        }
+        Ok(())
+    }
+}
+
 #[derive(Clone, PartialEq, Eq, Debug, Deserialize, Serialize)]
+#[non_exhaustive]
 pub struct InputInfo {
-    prevout: OutpointInfo,
-    sequence: elements::Sequence,
-    witness: InputWitnessInfo,
+    pub prevout: OutpointInfo,
+    pub is_pegin: bool,
+    pub script_sig: InputScriptInfo,
+    pub sequence: elements::Sequence,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub issuance: Option<AssetIssuanceInfo>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pegin: Option<PeginInfo>,
+    pub witness: InputWitnessInfo,
 }
 
 impl GetInfo<InputInfo> for elements::TxIn {
     fn get_info(&self, _network: Network) -> InputInfo {
         InputInfo {
             prevout: self.previous_output.get_info(_network),
+            is_pegin: self.is_pegin,
+            script_sig: self.script_sig.get_info(_network),
             sequence: self.sequence,
+            issuance: if self.has_issuance() {
+                Some(self.get_info(_network))
+            } else {
+                None
+            },
+            pegin: self.pegin_data().map(|data| data.get_info(_network)),
             witness: self.witness.get_info(_network),
         }
     }
 }
 
 #[derive(Clone, PartialEq, Eq, Debug, Deserialize, Serialize)]
+#[non_exhaustive]
+pub struct InputScriptInfo {
+    pub hex: String,
+    pub asm: String,
+    /// Type of the redeem script of P2SH-wrapped segwit inputs
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub type_: Option<String>,
+    /// Witness program of P2SH-wrapped segwit inputs
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub witness_program: Option<WitnessProgramInfo>,
+}
+
+impl GetInfo<InputScriptInfo> for elements::Script {
+    fn get_info(&self, _network: Network) -> InputScriptInfo {
+        let redeem_script = script::wrapped_witness_program(self);
+        let witness_program = redeem_script.as_ref().and_then(script::witness_program);
+
+        InputScriptInfo {
+            hex: self.to_hex(),
+            asm: self.asm(),
+            type_: witness_program
+                .map(|(version, program)| script::wrapped_segwit_type(version, program).to_owned()),
+            witness_program: redeem_script
+                .as_ref()
+                .and_then(script::witness_program_info),
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Debug, Deserialize, Serialize)]
+#[non_exhaustive]
+pub struct AssetIssuanceInfo {
+    pub is_reissuance: bool,
+    pub asset_id: String,
+    pub token_id: String,
+    pub entropy: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub contract_hash: Option<String>,
+    pub asset_blinding_nonce: String,
+    pub asset_entropy: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub amount: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub amount_commitment: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub inflation_keys: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub inflation_keys_commitment: Option<String>,
+}
+
+impl GetInfo<AssetIssuanceInfo> for elements::TxIn {
+    fn get_info(&self, _network: Network) -> AssetIssuanceInfo {
+        let issuance = &self.asset_issuance;
+        let (amount, amount_commitment) = value_info(issuance.amount);
+        let (inflation_keys, inflation_keys_commitment) = value_info(issuance.inflation_keys);
+        let (asset_id, token_id) = self.issuance_ids();
+
+        // New issuances commit to a contract hash, from which the entropy is derived.
+        // Reissuances carry the entropy of the original issuance.
+        let is_reissuance = issuance.asset_blinding_nonce != elements::secp256k1_zkp::ZERO_TWEAK;
+        let (entropy, contract_hash) = if is_reissuance {
+            let entropy =
+                elements::hashes::sha256::Midstate::from_byte_array(issuance.asset_entropy);
+            (entropy.to_string(), None)
+        } else {
+            let contract_hash = elements::ContractHash::from_byte_array(issuance.asset_entropy);
+            let entropy =
+                elements::AssetId::generate_asset_entropy(self.previous_output, contract_hash);
+            (entropy.to_string(), Some(contract_hash.to_string()))
+        };
+
+        AssetIssuanceInfo {
+            is_reissuance,
+            asset_id: asset_id.to_string(),
+            token_id: token_id.to_string(),
+            entropy,
+            contract_hash,
+            asset_blinding_nonce: issuance.asset_blinding_nonce.as_ref().to_hex(),
+            asset_entropy: issuance.asset_entropy.to_hex(),
+            amount,
+            amount_commitment,
+            inflation_keys,
+            inflation_keys_commitment,
+        }
+    }
+}
+
+/// Return the asset issuance of inputs without issuance.
+pub(crate) fn null_issuance() -> elements::AssetIssuance {
+    elements::AssetIssuance {
+        asset_blinding_nonce: elements::secp256k1_zkp::ZERO_TWEAK,
+        asset_entropy: [0; 32],
+        amount: confidential::Value::Null,
+        inflation_keys: confidential::Value::Null,
+    }
+}
+
+/// Split the given value into its explicit amount or hex-encoded commitment.
+fn value_info(value: confidential::Value) -> (Option<u64>, Option<String>) {
+    match value {
+        confidential::Value::Null => (None, None),
+        confidential::Value::Explicit(n) => (Some(n), None),
+        confidential::Value::Confidential(..) => (None, Some(encode::serialize(&value).to_hex())),
+    }
+}
+
+/// Parse a value from its explicit amount or hex-encoded commitment.
+///
+/// If neither is given, then the value is null.
+fn parse_value(
+    explicit: Option<u64>,
+    commitment: Option<&str>,
+) -> Result<confidential::Value, Error> {
+    match (explicit, commitment) {
+        (Some(n), _) => Ok(confidential::Value::Explicit(n)),
+        (None, Some(hex)) => Ok(encode::deserialize(&Vec::<u8>::from_hex(hex)?)?),
+        (None, None) => Ok(confidential::Value::Null),
+    }
+}
+
+/// Parse optional bytes from the given hex string.
+fn parse_optional_hex(hex: Option<&str>) -> Result<Option<Vec<u8>>, Error> {
+    hex.map(Vec::<u8>::from_hex)
+        .transpose()
+        .map_err(Error::from)
+}
+
+#[derive(Clone, PartialEq, Eq, Debug, Deserialize, Serialize)]
+#[non_exhaustive]
 pub struct OutpointInfo {
-    txid: elements::Txid,
-    vout: u32,
+    pub txid: elements::Txid,
+    pub vout: u32,
 }
 
 impl GetInfo<OutpointInfo> for elements::OutPoint {
@@ -72,10 +251,17 @@ impl GetInfo<OutpointInfo> for elements::OutPoint {
 }
 
 #[derive(Clone, PartialEq, Eq, Debug, Deserialize, Serialize)]
+#[non_exhaustive]
 pub struct InputWitnessInfo {
-    stack: Vec<String>,
-    is_key_spend: bool,
-    script_spend: Option<ScriptSpendInfo>,
+    pub stack: Vec<String>,
+    pub is_key_spend: bool,
+    pub script_spend: Option<ScriptSpendInfo>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub amount_rangeproof: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub inflation_keys_rangeproof: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub pegin_witness: Vec<String>,
 }
 
 impl GetInfo<InputWitnessInfo> for elements::TxInWitness {
@@ -87,6 +273,15 @@ impl GetInfo<InputWitnessInfo> for elements::TxInWitness {
             is_key_spend: is_key_spend(&self.script_witness),
             script_spend: ScriptSpendWitness::new(&self.script_witness)
                 .map(|x| x.get_info(_network)),
+            amount_rangeproof: self
+                .amount_rangeproof
+                .as_ref()
+                .map(|p| p.serialize().to_hex()),
+            inflation_keys_rangeproof: self
+                .inflation_keys_rangeproof
+                .as_ref()
+                .map(|p| p.serialize().to_hex()),
+            pegin_witness: self.pegin_witness.iter().map(|x| x.to_hex()).collect(),
         }
     }
 }
@@ -96,19 +291,32 @@ fn is_key_spend(script_witness: &[Vec<u8>]) -> bool {
 }
 
 #[derive(Clone, PartialEq, Eq, Debug, Deserialize, Serialize)]
+#[non_exhaustive]
 pub struct ScriptSpendInfo {
-    internal_key: String,
-    merkle_path: Vec<String>,
-    leaf_version: String,
-    script_inputs: Vec<String>,
-    script: String,
-    simplicity: Option<SimplicitySpendInfo>,
+    pub internal_key: String,
+    pub merkle_path: Vec<String>,
+    pub leaf_version: String,
+    pub script_inputs: Vec<String>,
+    pub script: String,
+    /// ASM of the leaf script, unless it is a Simplicity leaf
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub asm: Option<String>,
+    /// Miniscript fragments that make up the tapscript, if recognised
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub miniscript: Option<String>,
+    pub tapleaf_hash: String,
+    /// Merkle root that is reconstructed from the tapleaf hash and the merkle path
+    pub merkle_root: String,
+    /// Whether the control block commits to the key of the spent output, if it is known
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub commits_to_output_key: Option<bool>,
+    pub simplicity: Option<SimplicitySpendInfo>,
 }
 
-struct ScriptSpendWitness<'a> {
-    script_inputs: &'a [Vec<u8>],
-    script: &'a [u8],
-    control_block: elements::taproot::ControlBlock,
+pub(crate) struct ScriptSpendWitness<'a> {
+    pub(crate) script_inputs: &'a [Vec<u8>],
+    pub(crate) script: &'a [u8],
+    pub(crate) control_block: elements::taproot::ControlBlock,
 }
 
 impl<'a> ScriptSpendWitness<'a> {
@@ -140,93 +348,278 @@ impl<'a> GetInfo<ScriptSpendInfo> for ScriptSpendWitness<'a> {
             .map(|h| h.to_hex())
             .collect();
 
+        let leaf_script = self.leaf_script();
+        let leaf_version = self.control_block.leaf_version;
+        let is_tapscript = leaf_version == LeafVersion::default();
+        let commitment = self.leaf_commitment();
+
         ScriptSpendInfo {
             internal_key: self.control_block.internal_key.to_hex(),
             merkle_path,
-            leaf_version: self.control_block.leaf_version.as_u8().to_hex(),
+            leaf_version: leaf_version.as_u8().to_hex(),
             script_inputs: self.script_inputs.iter().map(|i| i.to_hex()).collect(),
             script: self.script.to_hex(),
-            simplicity: self.get_simplicity_spend_info(),
+            asm: commitment.asm,
+            miniscript: if is_tapscript {
+                script::miniscript_fragments(&leaf_script)
+            } else {
+                None
+            },
+            tapleaf_hash: commitment.tapleaf_hash.to_hex(),
+            merkle_root: commitment.merkle_root.to_hex(),
+            commits_to_output_key: None,
+            simplicity: self.get_simplicity_spend_info(_network),
+        }
+    }
+}
+
+/// Hashes that commit to a tapleaf, and the ASM of its script.
+pub(crate) struct TapLeafCommitment {
+    pub(crate) tapleaf_hash: TapLeafHash,
+    /// Merkle root that is reconstructed from the tapleaf hash and the merkle path
+    pub(crate) merkle_root: TapBranchHash,
+    /// ASM of the leaf script, unless it is a Simplicity leaf
+    pub(crate) asm: Option<String>,
+}
+
+impl<'a> ScriptSpendWitness<'a> {
+    /// Describe the spend of the given tapleaf without script inputs.
+    ///
+    /// This is useful for describing tapleaves before the spend is assembled, such as in a PSET.
+    pub fn from_leaf(script: &'a [u8], control_block: elements::taproot::ControlBlock) -> Self {
+        Self {
+            script_inputs: &[],
+            script,
+            control_block,
+        }
+    }
+
+    /// Return the script of the spent tapleaf.
+    pub fn leaf_script(&self) -> elements::Script {
+        elements::Script::from(self.script.to_vec())
+    }
+
+    /// Compute the tapleaf hash and merkle root of the spent tapleaf.
+    pub fn leaf_commitment(&self) -> TapLeafCommitment {
+        let leaf_script = self.leaf_script();
+        let tapleaf_hash = TapLeafHash::from_script(&leaf_script, self.control_block.leaf_version);
+        let merkle_root =
+            script::taproot_merkle_root(tapleaf_hash, self.control_block.merkle_branch.as_inner());
+
+        TapLeafCommitment {
+            tapleaf_hash,
+            merkle_root,
+            asm: if self.is_simplicity_leaf() {
+                None
+            } else {
+                Some(leaf_script.asm())
+            },
+        }
+    }
+
+    /// Return whether the control block commits to the output key of the given scriptPubKey.
+    ///
+    /// Outputs other than P2TR never match.
+    pub fn commits_to_output(&self, script_pubkey: &elements::Script) -> bool {
+        let secp = secp256k1_zkp::Secp256k1::verification_only();
+        match script::taproot_output_key(script_pubkey) {
+            Some(output_key) => self.control_block.verify_taproot_commitment(
+                &secp,
+                &output_key,
+                &self.leaf_script(),
+            ),
+            None => false,
         }
     }
 }
 
 #[derive(Clone, PartialEq, Eq, Debug, Deserialize, Serialize)]
+#[non_exhaustive]
 pub struct SimplicitySpendInfo {
-    program: String,
-    cmr: String,
+    pub program: String,
+    pub cmr: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub info: Option<ProgramInfo>,
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub nodes: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
 }
 
 impl<'a> ScriptSpendWitness<'a> {
+    /// Return whether the spent tapleaf has the Simplicity leaf version.
+    pub fn is_simplicity_leaf(&self) -> bool {
+        self.control_block.leaf_version.as_u8() == util::SIMPLICITY_LEAF_VERSION
+    }
+
     pub fn is_simplicity_spend(&self) -> bool {
-        self.control_block.leaf_version.as_u8() == 0xbe && self.script_inputs.len() == 1
+        self.is_simplicity_leaf() && self.script_inputs.len() == 1
     }
 
-    pub fn get_simplicity_spend_info(&self) -> Option<SimplicitySpendInfo> {
-        if !self.is_simplicity_spend() {
+    /// Return information about the Simplicity program of the spend,
+    /// or `None` if the spent tapleaf does not have the Simplicity leaf version.
+    pub fn get_simplicity_spend_info(&self, network: Network) -> Option<SimplicitySpendInfo> {
+        if !self.is_simplicity_leaf() {
             return None;
         }
 
-        let program_and_witness_bytes = &self.script_inputs[0];
         let cmr_bytes = self.script;
+        let mut info = SimplicitySpendInfo {
+            program: String::new(),
+            cmr: cmr_bytes.to_hex(),
+            info: None,
+            nodes: vec![],
+            error: None,
+        };
+        if self.script_inputs.len() != 1 {
+            info.error = Some(format!(
+                "expected 1 script input for Simplicity spend, found {}",
+                self.script_inputs.len()
+            ));
+            return Some(info);
+        }
 
-        // FIXME: Does this work with trailing padding?
-        let base64 = encode::encode_base64(|w| w.write(program_and_witness_bytes)).ok()?;
-        let cmr = cmr_bytes.to_hex();
+        let program_and_witness_bytes = &self.script_inputs[0];
+        info.program = general_purpose::STANDARD.encode(program_and_witness_bytes);
 
-        Some(SimplicitySpendInfo {
-            program: base64,
-            cmr,
-        })
+        match decode::decode_finalized_program_bytes::<Elements>(program_and_witness_bytes) {
+            Ok(program) => {
+                if program.cmr().as_ref() != cmr_bytes {
+                    info.error = Some(format!(
+                        "CMR of program {} does not match tapleaf script {}",
+                        program.cmr(),
+                        info.cmr,
+                    ));
+                }
+                info.info = Some(program.get_info(network));
+                info.nodes = prog::list_nodes(program.as_ref());
+            }
+            Err(error) => info.error = Some(error.to_string()),
+        }
+
+        Some(info)
     }
 }
 
 #[derive(Clone, PartialEq, Eq, Debug, Deserialize, Serialize)]
+#[non_exhaustive]
 pub struct OutputInfo {
-    script_pub_key: OutputScriptInfo,
-    value: Option<u64>,
-    asset: Option<String>,
-    is_fee: bool,
+    pub script_pub_key: OutputScriptInfo,
+    pub value: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value_commitment: Option<String>,
+    pub asset: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub asset_commitment: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nonce: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ecdh_pubkey: Option<String>,
+    #[serde(default)]
+    pub confidential: ConfidentialityInfo,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rangeproof: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rangeproof_size: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub surjection_proof: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub surjection_proof_size: Option<usize>,
+    pub is_fee: bool,
+}
+
+/// Which fields of an output are blinded.
+#[derive(Clone, PartialEq, Eq, Debug, Default, Deserialize, Serialize)]
+#[non_exhaustive]
+pub struct ConfidentialityInfo {
+    pub value: bool,
+    pub asset: bool,
+    pub nonce: bool,
 }
 
 impl GetInfo<OutputInfo> for elements::TxOut {
     fn get_info(&self, network: Network) -> OutputInfo {
-        let value = if let confidential::Value::Explicit(n) = self.value {
-            Some(n)
-        } else {
-            None
+        let (value, value_commitment) = value_info(self.value);
+        let (asset, asset_commitment) = match self.asset {
+            confidential::Asset::Null => (None, None),
+            confidential::Asset::Explicit(asset_id) => (Some(asset_id.to_hex()), None),
+            confidential::Asset::Confidential(..) => {
+                (None, Some(encode::serialize(&self.asset).to_hex()))
+            }
         };
-        let asset = if let confidential::Asset::Explicit(asset_id) = self.asset {
-            Some(asset_id.to_hex())
-        } else {
+        let nonce = if self.nonce.is_null() {
             None
+        } else {
+            Some(encode::serialize(&self.nonce).to_hex())
+        };
+        let ecdh_pubkey = match self.nonce {
+            confidential::Nonce::Confidential(pubkey) => Some(pubkey.to_string()),
+            _ => None,
         };
+        let rangeproof = self.witness.rangeproof.as_ref().map(|p| p.serialize());
+        let surjection_proof = self
+            .witness
+            .surjection_proof
+            .as_ref()
+            .map(|p| p.serialize());
 
         OutputInfo {
             script_pub_key: self.script_pubkey.get_info(network),
             value,
+            value_commitment,
             asset,
+            asset_commitment,
+            nonce,
+            ecdh_pubkey,
+            confidential: ConfidentialityInfo {
+                value: self.value.is_confidential(),
+                asset: self.asset.is_confidential(),
+                nonce: self.nonce.is_confidential(),
+            },
+            rangeproof_size: rangeproof.as_ref().map(Vec::len),
+            rangeproof: rangeproof.map(|p| p.to_hex()),
+            surjection_proof_size: surjection_proof.as_ref().map(Vec::len),
+            surjection_proof: surjection_proof.map(|p| p.to_hex()),
             is_fee: self.is_fee(),
         }
     }
 }
 
 #[derive(Clone, PartialEq, Eq, Debug, Deserialize, Serialize)]
+#[non_exhaustive]
 pub struct OutputScriptInfo {
-    hex: String,
-    asm: String,
+    pub hex: String,
+    pub asm: String,
     #[serde(rename = "type")]
-    type_: String,
+    pub type_: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub address: Option<elements::Address>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pegout: Option<PegoutInfo>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub witness_program: Option<WitnessProgramInfo>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    address: Option<elements::Address>,
+    pub multisig: Option<MultisigInfo>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub op_return: Option<OpReturnInfo>,
 }
 
 impl GetInfo<OutputScriptInfo> for elements::Script {
     fn get_info(&self, network: Network) -> OutputScriptInfo {
-        let type_ = if self.is_p2pk() {
+        let pegout = peg::pegout_info(self);
+        let witness_program = script::witness_program_info(self);
+        let multisig = script::multisig_info(self);
+        let op_return = script::op_return_info(self);
+        let type_ = if pegout.is_some() {
+            "pegout"
+        } else if self.is_empty() {
+            "fee"
+        } else if self.is_p2pk() {
             "p2pk"
         } else if self.is_p2pkh() {
             "p2pkh"
+        } else if multisig.is_some() {
+            "multisig"
         } else if self.is_op_return() {
             "opreturn"
         } else if self.is_p2sh() {
@@ -237,6 +630,8 @@ impl GetInfo<OutputScriptInfo> for elements::Script {
             "p2wsh"
         } else if self.is_v1_p2tr() {
             "p2tr"
+        } else if witness_program.is_some() {
+            "witness_unknown"
         } else {
             "unknown"
         }
@@ -248,6 +643,355 @@ impl GetInfo<OutputScriptInfo> for elements::Script {
             asm: self.asm(),
             type_,
             address,
+            pegout,
+            witness_program,
+            multisig,
+            op_return,
+        }
+    }
+}
+
+// Encoding of the JSON descriptions back into transactions.
+// Fields that are computed during decoding, such as `txid` or `asm`, are ignored,
+// except that the txid and wtxid are checked against the encoded transaction.
+
+impl TransactionInfo {
+    /// Encode the description as a transaction.
+    pub fn to_transaction(&self) -> Result<elements::Transaction, Error> {
+        let tx = elements::Transaction {
+            version: self.version,
+            lock_time: self.locktime,
+            input: self
+                .inputs
+                .iter()
+                .enumerate()
+                .map(|(i, input)| input.to_txin().with_argument(&format!("inputs[{}]", i)))
+                .collect::<Result<_, _>>()?,
+            output: self
+                .outputs
+                .iter()
+                .enumerate()
+                .map(|(i, output)| output.to_txout().with_argument(&format!("outputs[{}]", i)))
+                .collect::<Result<_, _>>()?,
+        };
+
+        if tx.txid() != self.txid {
+            return Err(Error::TxidMismatch(
+                tx.txid().to_string(),
+                self.txid.to_string(),
+            ));
+        }
+        for (field, wtxid) in [("wtxid", self.wtxid), ("hash", self.hash)] {
+            if tx.wtxid() != wtxid {
+                return Err(Error::WtxidMismatch(
+                    tx.wtxid().to_string(),
+                    field,
+                    wtxid.to_string(),
+                ));
+            }
+        }
+        Ok(tx)
+    }
+}
+
+impl InputInfo {
+    /// Encode the description as a transaction input.
+    pub fn to_txin(&self) -> Result<elements::TxIn, Error> {
+        let asset_issuance = match &self.issuance {
+            Some(issuance) => issuance.to_asset_issuance()?,
+            None => null_issuance(),
+        };
+
+        Ok(elements::TxIn {
+            previous_output: elements::OutPoint::new(self.prevout.txid, self.prevout.vout),
+            is_pegin: self.is_pegin,
+            script_sig: elements::Script::from(Vec::<u8>::from_hex(&self.script_sig.hex)?),
+            sequence: self.sequence,
+            asset_issuance,
+            witness: self.witness.to_witness()?,
+        })
+    }
+}
+
+impl AssetIssuanceInfo {
+    /// Encode the description as an asset issuance.
+    pub fn to_asset_issuance(&self) -> Result<elements::AssetIssuance, Error> {
+        let nonce_bytes = Vec::<u8>::from_hex(&self.asset_blinding_nonce)?;
+        let entropy_bytes = Vec::<u8>::from_hex(&self.asset_entropy)?;
+        let mut asset_entropy = [0; 32];
+        if entropy_bytes.len() != asset_entropy.len() {
+            return Err(Error::Length("asset_entropy", 32, entropy_bytes.len()));
+        }
+        asset_entropy.copy_from_slice(&entropy_bytes);
+
+        Ok(elements::AssetIssuance {
+            asset_blinding_nonce: Tweak::from_slice(&nonce_bytes)?,
+            asset_entropy,
+            amount: parse_value(self.amount, self.amount_commitment.as_deref())?,
+            inflation_keys: parse_value(
+                self.inflation_keys,
+                self.inflation_keys_commitment.as_deref(),
+            )?,
+        })
+    }
+}
+
+impl InputWitnessInfo {
+    /// Encode the description as an input witness.
+    pub fn to_witness(&self) -> Result<elements::TxInWitness, Error> {
+        let parse_rangeproof = |hex: Option<&str>| -> Result<_, Error> {
+            match parse_optional_hex(hex)? {
+                Some(bytes) => Ok(Some(Box::new(RangeProof::from_slice(&bytes)?))),
+                None => Ok(None),
+            }
+        };
+
+        Ok(elements::TxInWitness {
+            amount_rangeproof: parse_rangeproof(self.amount_rangeproof.as_deref())?,
+            inflation_keys_rangeproof: parse_rangeproof(self.inflation_keys_rangeproof.as_deref())?,
+            script_witness: self
+                .stack
+                .iter()
+                .map(|hex| Vec::<u8>::from_hex(hex))
+                .collect::<Result<_, _>>()?,
+            pegin_witness: self
+                .pegin_witness
+                .iter()
+                .map(|hex| Vec::<u8>::from_hex(hex))
+                .collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+impl OutputInfo {
+    /// Encode the description as a transaction output.
+    pub fn to_txout(&self) -> Result<elements::TxOut, Error> {
+        let asset = match (&self.asset, &self.asset_commitment) {
+            (Some(asset_id), _) => {
+                confidential::Asset::Explicit(elements::AssetId::from_str(asset_id)?)
+            }
+            (None, Some(hex)) => encode::deserialize(&Vec::<u8>::from_hex(hex)?)?,
+            (None, None) => confidential::Asset::Null,
+        };
+        let nonce = match parse_optional_hex(self.nonce.as_deref())? {
+            Some(bytes) => encode::deserialize(&bytes)?,
+            None => confidential::Nonce::Null,
+        };
+        let rangeproof = match parse_optional_hex(self.rangeproof.as_deref())? {
+            Some(bytes) => Some(Box::new(RangeProof::from_slice(&bytes)?)),
+            None => None,
+        };
+        let surjection_proof = match parse_optional_hex(self.surjection_proof.as_deref())? {
+            Some(bytes) => Some(Box::new(SurjectionProof::from_slice(&bytes)?)),
+            None => None,
+        };
+
+        Ok(elements::TxOut {
+            asset,
+            value: parse_value(self.value, self.value_commitment.as_deref())?,
+            nonce,
+            script_pubkey: elements::Script::from(Vec::<u8>::from_hex(&self.script_pub_key.hex)?),
+            witness: elements::TxOutWitness {
+                surjection_proof,
+                rangeproof,
+            },
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use elements::bitcoin;
+    use elements::confidential::{AssetBlindingFactor, ValueBlindingFactor};
+    use elements::secp256k1_zkp::{PublicKey, SecretKey};
+    use elements::{TxInWitness, TxOutSecrets};
+
+    use super::*;
+    use crate::{address, create};
+
+    /// P2WPKH spend with explicit outputs, including a fee output
+    const EXPLICIT_TX: &str = "\
+        02000000010184fd9bac333ad79154348296204fa7f8c537a96e08983e5f73b3f5aca8e8edf70100000000fd\
+        ffffff02016f0279e9ed041c3d710a9f57d0c02928416460c4b722ae3457a11eec381c526d010000000005f5\
+        b9f000160014751e76e8199196d454941c45d1b3a323f1433bd6016f0279e9ed041c3d710a9f57d0c0292841\
+        6460c4b722ae3457a11eec381c526d0100000000000027100000000000000000024730440220454349e422f0\
+        5297191ead13e21d3db520e5abef52055e4964b82fb213f593a10220013a718774c572bd8a25adbeb1bfcd5c\
+        0256ae11cecf9f9c3f925d0e52beaf8901210279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959\
+        f2815b16f817980000000000";
+
+    /// New asset issuance with reissuance token
+    const ISSUANCE_TX: &str = "\
+        02000000010184fd9bac333ad79154348296204fa7f8c537a96e08983e5f73b3f5aca8e8edf70000008000fd\
+        ffffff0000000000000000000000000000000000000000000000000000000000000000cc8321d6375c494d04\
+        3fdd0260f21bc0ec51dacc9f6abb7f909cdcd3041b78bf01000775f05a0740000100000000000000010401d5\
+        9386e0ae435e292fbe0ebcdb954b75ed5fb3922091277cb19f798fc5d5071801000775f05a07400000160014\
+        751e76e8199196d454941c45d1b3a323f1433bd6013c469e9d6c5875d37a43f353d4f88e61fcf812c66eee34\
+        57465a40b0da4153e001000000000000000100160014751e76e8199196d454941c45d1b3a323f1433bd6016f\
+        0279e9ed041c3d710a9f57d0c02928416460c4b722ae3457a11eec381c526d010000000005f5b9f000160014\
+        751e76e8199196d454941c45d1b3a323f1433bd6016f0279e9ed041c3d710a9f57d0c02928416460c4b722ae\
+        3457a11eec381c526d0100000000000027100000000000000000024730440220454349e422f05297191ead13\
+        e21d3db520e5abef52055e4964b82fb213f593a10220013a718774c572bd8a25adbeb1bfcd5c0256ae11cecf\
+        9f9c3f925d0e52beaf8901210279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f817\
+        98000000000000000000";
+
+    /// Reissuance of an asset, spending the reissuance token
+    const REISSUANCE_TX: &str = "\
+        0200000001020558f9ca1357f41fb70d35b1a8230d4296f498c7e80ff18c352653c4304b17ab0100008000fd\
+        ffffff3a377b525757b494427f89014f97d79928f3938d14eb51e20fb5dec9834eb30467671a2f53dd910a8b\
+        35840edb6a0a1e751ae5532178ca7f025b823eee31799201000000000007a1200084fd9bac333ad791543482\
+        96204fa7f8c537a96e08983e5f73b3f5aca8e8edf70200000000fdffffff0301d59386e0ae435e292fbe0ebc\
+        db954b75ed5fb3922091277cb19f798fc5d5071801000000000007a12000160014751e76e8199196d454941c\
+        45d1b3a323f1433bd6013c469e9d6c5875d37a43f353d4f88e61fcf812c66eee3457465a40b0da4153e00100\
+        0000000000000100160014751e76e8199196d454941c45d1b3a323f1433bd6016f0279e9ed041c3d710a9f57\
+        d0c02928416460c4b722ae3457a11eec381c526d010000000000002710000000000000000002473044022045\
+        4349e422f05297191ead13e21d3db520e5abef52055e4964b82fb213f593a10220013a718774c572bd8a25ad\
+        beb1bfcd5c0256ae11cecf9f9c3f925d0e52beaf8901210279be667ef9dcbbac55a06295ce870b07029bfcdb\
+        2dce28d959f2815b16f81798000000024730440220454349e422f05297191ead13e21d3db520e5abef52055e\
+        4964b82fb213f593a10220013a718774c572bd8a25adbeb1bfcd5c0256ae11cecf9f9c3f925d0e52beaf8901\
+        210279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f8179800000000000000";
+
+    /// Peg-in claiming a mainchain output to a P2WSH of the claim script
+    const PEGIN_TX: &str = "\
+        02000000010104c35faa019b14a60cc7af354b58c07a78c08fb6fc0ebba6e8107ad5c6dc77960000004000ff\
+        ffffff02016f0279e9ed041c3d710a9f57d0c02928416460c4b722ae3457a11eec381c526d010000000005f5\
+        b9f000160014751e76e8199196d454941c45d1b3a323f1433bd6016f0279e9ed041c3d710a9f57d0c0292841\
+        6460c4b722ae3457a11eec381c526d0100000000000027100000000000000000024730440220454349e422f0\
+        5297191ead13e21d3db520e5abef52055e4964b82fb213f593a10220013a718774c572bd8a25adbeb1bfcd5c\
+        0256ae11cecf9f9c3f925d0e52beaf8901210279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959\
+        f2815b16f81798060800e1f50500000000206f0279e9ed041c3d710a9f57d0c02928416460c4b722ae3457a1\
+        1eec381c526d206fe28c0ab6f1b372c1a6a246ae63f74f931e8365e15a089c68d6190000000000160014751e\
+        76e8199196d454941c45d1b3a323f1433bd65e0200000001c02b8840fd9a1054fcc6f71312a75b907279f916\
+        41d43caba9797326a3b07e6d0000000000feffffff0100e1f505000000002200208838f796bf4970b148779c\
+        05b74b8c49515b322d04035f7faa5d9b2375df2396000000007700000020a50a6e9b7af48194bbdbe52c83d6\
+        312587c02b00f7778b511487dc1f1547ee0e04c35faa019b14a60cc7af354b58c07a78c08fb6fc0ebba6e810\
+        7ad5c6dc779600f15365ffff001d2a000000010000000104c35faa019b14a60cc7af354b58c07a78c08fb6fc\
+        0ebba6e8107ad5c6dc7796010100000000";
+
+    fn assert_round_trip(bytes: &[u8]) -> TransactionInfo {
+        let tx: elements::Transaction = encode::deserialize(bytes).unwrap();
+        let json = serde_json::to_string(&tx.get_info(Network::ElementsRegtest)).unwrap();
+        let info: TransactionInfo = serde_json::from_str(&json).unwrap();
+        assert_eq!(encode::serialize(&info.to_transaction().unwrap()), bytes);
+        info
+    }
+
+    fn explicit_tx() -> elements::Transaction {
+        encode::deserialize(&Vec::<u8>::from_hex(EXPLICIT_TX).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn round_trip_explicit() {
+        let info = assert_round_trip(&Vec::<u8>::from_hex(EXPLICIT_TX).unwrap());
+        assert!(info.outputs.iter().all(|output| output.value.is_some()));
+        assert!(info.outputs[1].is_fee);
+    }
+
+    #[test]
+    fn wtxid_mismatch() {
+        let mut info = explicit_tx().get_info(Network::ElementsRegtest);
+        info.inputs[0].witness.stack.pop();
+        match info.to_transaction() {
+            Err(Error::WtxidMismatch(_, "wtxid", _)) => {}
+            result => panic!("unexpected result {:?}", result),
+        }
+    }
+
+    #[test]
+    fn round_trip_confidential() {
+        let secp = secp256k1_zkp::Secp256k1::new();
+        let blinding_key = SecretKey::from_slice(&[1; 32]).unwrap();
+        let key = SecretKey::from_slice(&[2; 32]).unwrap();
+        let address = elements::Address::p2wpkh(
+            &bitcoin::PublicKey::new(PublicKey::from_secret_key(&secp, &key)),
+            Some(PublicKey::from_secret_key(&secp, &blinding_key)),
+            &elements::AddressParams::ELEMENTS,
+        );
+        let asset = elements::AssetId::from_slice(&[3; 32]).unwrap();
+        let spent = TxOutSecrets::new(
+            asset,
+            AssetBlindingFactor::zero(),
+            100_000,
+            ValueBlindingFactor::zero(),
+        );
+        let (output, ..) = elements::TxOut::new_not_last_confidential(
+            &mut rand::thread_rng(),
+            &secp,
+            90_000,
+            address,
+            asset,
+            &[spent],
+        )
+        .unwrap();
+
+        let mut tx = explicit_tx();
+        tx.output[0] = output;
+        let info = assert_round_trip(&encode::serialize(&tx));
+        assert!(info.outputs[0].value_commitment.is_some());
+        assert!(info.outputs[0].rangeproof.is_some());
+        assert!(info.outputs[0].surjection_proof.is_some());
+    }
+
+    #[test]
+    fn round_trip_issuance() {
+        let info = assert_round_trip(&Vec::<u8>::from_hex(ISSUANCE_TX).unwrap());
+        let issuance = info.inputs[0].issuance.as_ref().unwrap();
+        assert!(!issuance.is_reissuance);
+    }
+
+    #[test]
+    fn round_trip_reissuance() {
+        let info = assert_round_trip(&Vec::<u8>::from_hex(REISSUANCE_TX).unwrap());
+        let issuance = info.inputs[0].issuance.as_ref().unwrap();
+        assert!(issuance.is_reissuance);
+        assert!(info.inputs[1].issuance.is_none());
+    }
+
+    #[test]
+    fn round_trip_pegin() {
+        let info = assert_round_trip(&Vec::<u8>::from_hex(PEGIN_TX).unwrap());
+        assert!(info.inputs[0].is_pegin);
+        assert_eq!(info.inputs[0].witness.pegin_witness.len(), 6);
+    }
+
+    #[test]
+    fn round_trip_simplicity_spend() {
+        // The program `unit` without witness data
+        let program = decode::decode_finalized_program_bytes::<Elements>(&[0x24]).unwrap();
+        let spend_info =
+            address::simplicity_spend_info(program.cmr(), address::unspendable_internal_key(), &[])
+                .unwrap();
+        let control_block = address::simplicity_control_block(&spend_info, program.cmr()).unwrap();
+
+        let mut tx = explicit_tx();
+        tx.input[0].witness = TxInWitness {
+            script_witness: create::simplicity_witness_stack(&program, &control_block).unwrap(),
+            ..Default::default()
+        };
+        let info = assert_round_trip(&encode::serialize(&tx));
+        let script_spend = info.inputs[0].witness.script_spend.as_ref().unwrap();
+        let simplicity = script_spend.simplicity.as_ref().unwrap();
+        assert!(simplicity.error.is_none());
+    }
+
+    #[test]
+    fn simplicity_spend_wrong_input_count() {
+        let program = decode::decode_finalized_program_bytes::<Elements>(&[0x24]).unwrap();
+        let spend_info =
+            address::simplicity_spend_info(program.cmr(), address::unspendable_internal_key(), &[])
+                .unwrap();
+        let control_block = address::simplicity_control_block(&spend_info, program.cmr()).unwrap();
+        let stack = create::simplicity_witness_stack(&program, &control_block).unwrap();
+
+        for script_witness in [stack[1..].to_vec(), [vec![vec![]], stack].concat()] {
+            let spend = ScriptSpendWitness::new(&script_witness).unwrap();
+            let simplicity = spend
+                .get_simplicity_spend_info(Network::ElementsRegtest)
+                .unwrap();
+            assert!(simplicity
+                .error
+                .unwrap()
+                .starts_with("expected 1 script input"));
+            assert!(simplicity.info.is_none());
         }
     }
 }
@@ -0,0 +1,71 @@
+use elements::secp256k1_zkp::{self, SecretKey};
+use serde::{Deserialize, Serialize};
+use simplicity::elements;
+
+#[derive(Clone, PartialEq, Eq, Debug, Deserialize, Serialize)]
+#[non_exhaustive]
+pub struct UnblindedOutputInfo {
+    pub index: usize,
+    pub is_unblinded: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub blinding_key_index: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub asset: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value_blinding_factor: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub asset_blinding_factor: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Unblind the confidential outputs of the given transaction.
+///
+/// Each output is unblinded with the first given blinding key that rewinds its rangeproof.
+/// Explicit outputs are skipped.
+/// Outputs that cannot be unblinded with any key are reported with the error of the last key.
+pub fn unblind_outputs(
+    tx: &elements::Transaction,
+    blinding_keys: &[SecretKey],
+) -> Vec<UnblindedOutputInfo> {
+    let secp = secp256k1_zkp::Secp256k1::verification_only();
+
+    tx.output
+        .iter()
+        .enumerate()
+        .filter(|(_, output)| output.value.is_confidential())
+        .map(|(index, output)| {
+            let mut error = None;
+            for (key_index, key) in blinding_keys.iter().enumerate() {
+                match output.unblind(&secp, *key) {
+                    Ok(secrets) => {
+                        return UnblindedOutputInfo {
+                            index,
+                            is_unblinded: true,
+                            blinding_key_index: Some(key_index),
+                            value: Some(secrets.value),
+                            asset: Some(secrets.asset.to_string()),
+                            value_blinding_factor: Some(secrets.value_bf.to_string()),
+                            asset_blinding_factor: Some(secrets.asset_bf.to_string()),
+                            error: None,
+                        }
+                    }
+                    Err(e) => error = Some(e.to_string()),
+                }
+            }
+
+            UnblindedOutputInfo {
+                index,
+                is_unblinded: false,
+                blinding_key_index: None,
+                value: None,
+                asset: None,
+                value_blinding_factor: None,
+                asset_blinding_factor: None,
+                error,
+            }
+        })
+        .collect()
+}
@@ -10,14 +10,18 @@ use simplicity::elements;
 pub enum Network {
     ElementsRegtest,
     Liquid,
+    LiquidTestnet,
+    /// Network with user-supplied address parameters
+    #[serde(skip)]
+    Custom(&'static elements::AddressParams),
 }
 
 impl Network {
-    #[allow(dead_code)]
     pub fn from_params(params: &'static elements::AddressParams) -> Option<Network> {
         match *params {
             elements::AddressParams::ELEMENTS => Some(Network::ElementsRegtest),
             elements::AddressParams::LIQUID => Some(Network::Liquid),
+            elements::AddressParams::LIQUID_TESTNET => Some(Network::LiquidTestnet),
             _ => None,
         }
     }
@@ -26,10 +30,30 @@ impl Network {
         match self {
             Network::ElementsRegtest => &elements::AddressParams::ELEMENTS,
             Network::Liquid => &elements::AddressParams::LIQUID,
+            Network::LiquidTestnet => &elements::AddressParams::LIQUID_TESTNET,
+            Network::Custom(params) => params,
         }
     }
 }
 
+/// User-supplied address parameters of a network.
+#[derive(Clone, PartialEq, Eq, Debug, Deserialize, Serialize)]
+pub struct AddressParamsInfo {
+    pub p2pkh_prefix: u8,
+    pub p2sh_prefix: u8,
+    pub blinded_prefix: u8,
+    pub bech_hrp: String,
+    pub blech_hrp: String,
+}
+
+/// Tapleaf version of Simplicity programs.
+pub const SIMPLICITY_LEAF_VERSION: u8 = 0xbe;
+
+/// Return the tapleaf version of Simplicity programs.
+pub fn simplicity_leaf_version() -> elements::taproot::LeafVersion {
+    elements::taproot::LeafVersion::from_u8(SIMPLICITY_LEAF_VERSION).expect("valid leaf version")
+}
+
 /// Get JSON-able objects that describe the type.
 pub trait GetInfo<T: Serialize> {
     /// Get a description of this object given the network of interest.
@@ -0,0 +1,132 @@
+use serde::{Deserialize, Serialize};
+use simplicity::elements;
+use simplicity::jet::elements::ElementsUtxo;
+use simplicity::jet::Elements;
+
+use crate::exec::{self, RunInfo};
+use crate::tx::ScriptSpendWitness;
+use crate::{decode, env};
+
+#[derive(Clone, PartialEq, Eq, Debug, Deserialize, Serialize)]
+#[non_exhaustive]
+pub struct VerifyInfo {
+    pub success: bool,
+    pub inputs: Vec<InputVerifyInfo>,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug, Deserialize, Serialize)]
+#[non_exhaustive]
+pub struct InputVerifyInfo {
+    pub index: u32,
+    pub is_simplicity_spend: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub success: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub run: Option<RunInfo>,
+}
+
+impl InputVerifyInfo {
+    fn fail(index: u32, reason: String) -> Self {
+        Self {
+            index,
+            is_simplicity_spend: true,
+            success: Some(false),
+            reason: Some(reason),
+            run: None,
+        }
+    }
+}
+
+/// Verify the Simplicity spends of the given transaction.
+///
+/// Each input that spends a tapleaf with the Simplicity leaf version is checked against
+/// its spent output and its program is executed in the Elements jet environment.
+/// Other inputs are skipped.
+pub fn verify_transaction(
+    tx: &elements::Transaction,
+    utxos: &[ElementsUtxo],
+    genesis_hash: elements::BlockHash,
+) -> VerifyInfo {
+    let inputs: Vec<_> = (0..tx.input.len() as u32)
+        .map(|index| verify_input(tx, utxos, index, genesis_hash))
+        .collect();
+
+    VerifyInfo {
+        success: inputs.iter().all(|i| i.success != Some(false)),
+        inputs,
+    }
+}
+
+fn verify_input(
+    tx: &elements::Transaction,
+    utxos: &[ElementsUtxo],
+    index: u32,
+    genesis_hash: elements::BlockHash,
+) -> InputVerifyInfo {
+    let script_witness = &tx.input[index as usize].witness.script_witness;
+    let spend = match ScriptSpendWitness::new(script_witness) {
+        Some(spend) if spend.is_simplicity_leaf() => spend,
+        _ => {
+            return InputVerifyInfo {
+                index,
+                is_simplicity_spend: false,
+                success: None,
+                reason: None,
+                run: None,
+            }
+        }
+    };
+
+    if spend.script_inputs.len() != 1 {
+        return InputVerifyInfo::fail(
+            index,
+            format!(
+                "expected 1 script input for Simplicity spend, found {}",
+                spend.script_inputs.len()
+            ),
+        );
+    }
+
+    let utxo = match utxos.get(index as usize) {
+        Some(utxo) => utxo,
+        None => return InputVerifyInfo::fail(index, "missing spent output".to_owned()),
+    };
+    if !spend.commits_to_output(&utxo.script_pubkey) {
+        return InputVerifyInfo::fail(
+            index,
+            "control block does not commit to spent output key".to_owned(),
+        );
+    }
+
+    let program = match decode::decode_finalized_program_bytes::<Elements>(&spend.script_inputs[0])
+    {
+        Ok(program) => program,
+        Err(error) => return InputVerifyInfo::fail(index, error.to_string()),
+    };
+    if program.cmr().as_ref() != spend.script {
+        return InputVerifyInfo::fail(index, "CMR does not match tapleaf script".to_owned());
+    }
+
+    let env = match env::build_env(
+        tx.clone(),
+        utxos.to_vec(),
+        index,
+        program.cmr(),
+        Some(spend.control_block.clone()),
+        genesis_hash,
+    ) {
+        Ok(env) => env,
+        Err(error) => return InputVerifyInfo::fail(index, error.to_string()),
+    };
+    let run = exec::run_program(program.as_ref(), &env);
+
+    InputVerifyInfo {
+        index,
+        is_simplicity_spend: true,
+        success: Some(run.success),
+        reason: run.error.clone(),
+        run: Some(run),
+    }
+}
@@ -0,0 +1,187 @@
+use std::sync::Arc;
+
+use elements::hex::{FromHex, ToHex};
+use serde::{Deserialize, Serialize};
+use simplicity::dag::{DagLike, MaxSharing};
+use simplicity::elements;
+use simplicity::jet::Jet;
+use simplicity::node::Inner;
+use simplicity::types::{CompleteBound, Final};
+use simplicity::{BitIter, CommitNode, RedeemNode, Value};
+
+use crate::encode;
+use crate::error::Error;
+
+#[derive(Clone, PartialEq, Eq, Debug, Deserialize, Serialize)]
+#[non_exhaustive]
+pub struct WitnessSlotInfo {
+    pub index: usize,
+    pub node: usize,
+    #[serde(rename = "type")]
+    pub type_: String,
+    pub bit_width: usize,
+}
+
+/// Return the types of the witness nodes of the given program.
+///
+/// The types are listed in the order in which witness values are consumed during finalization.
+pub fn witness_types<J: Jet>(program: &CommitNode<J>) -> Vec<Arc<Final>> {
+    witness_slots(program)
+        .into_iter()
+        .map(|(_, ty)| ty)
+        .collect()
+}
+
+/// Return the post-order index and type of each witness node of the given program.
+fn witness_slots<J: Jet>(program: &CommitNode<J>) -> Vec<(usize, Arc<Final>)> {
+    program
+        .post_order_iter::<MaxSharing<_>>()
+        .filter(|item| matches!(item.node.inner(), Inner::Witness(..)))
+        .map(|item| (item.index, item.node.arrow().target.clone()))
+        .collect()
+}
+
+/// Describe the witness slots of the given program.
+pub fn list_witness_slots<J: Jet>(program: &CommitNode<J>) -> Vec<WitnessSlotInfo> {
+    witness_slots(program)
+        .into_iter()
+        .enumerate()
+        .map(|(index, (node, ty))| WitnessSlotInfo {
+            index,
+            node,
+            type_: ty.to_string(),
+            bit_width: ty.bit_width(),
+        })
+        .collect()
+}
+
+/// Read a value of the given type from its compact bit encoding.
+fn value_from_bits<I: Iterator<Item = bool>>(bits: &mut I, ty: &Final) -> Option<Arc<Value>> {
+    match ty.bound() {
+        CompleteBound::Unit => Some(Value::unit()),
+        CompleteBound::Sum(left, right) => {
+            if bits.next()? {
+                Some(Value::sum_r(value_from_bits(bits, right)?))
+            } else {
+                Some(Value::sum_l(value_from_bits(bits, left)?))
+            }
+        }
+        CompleteBound::Product(left, right) => {
+            let value_left = value_from_bits(bits, left)?;
+            let value_right = value_from_bits(bits, right)?;
+            Some(Value::prod(value_left, value_right))
+        }
+    }
+}
+
+/// Parse a witness value of the given type from the given hex string.
+///
+/// The hex string encodes the bits of the value, padded with zeroes to a full byte.
+pub fn parse_witness_value(hex: &str, ty: &Final) -> Result<Arc<Value>, Error> {
+    let bytes = Vec::<u8>::from_hex(hex)?;
+    let mut bits = BitIter::new(bytes.into_iter());
+    let value = value_from_bits(&mut bits, ty)
+        .ok_or_else(|| Error::WitnessValue(hex.to_owned(), ty.to_string()))?;
+
+    let padding: Vec<bool> = bits.collect();
+    if padding.len() >= 8 || padding.iter().any(|bit| *bit) {
+        return Err(Error::WitnessValue(hex.to_owned(), ty.to_string()));
+    }
+    Ok(value)
+}
+
+/// Finalize the given program using witness values given as hex strings, one per witness slot.
+pub fn finalize_with_hex<J: Jet>(
+    program: &CommitNode<J>,
+    values: &[String],
+) -> Result<Arc<RedeemNode<J>>, Error> {
+    let types = witness_types(program);
+    if values.len() != types.len() {
+        return Err(Error::WitnessCount(values.len(), types.len()));
+    }
+    let values = values
+        .iter()
+        .zip(types.iter())
+        .map(|(hex, ty)| parse_witness_value(hex, ty))
+        .collect::<Result<Vec<_>, Error>>()?;
+
+    let program = program.finalize(values.into_iter())?;
+    Ok(program)
+}
+
+#[derive(Clone, PartialEq, Eq, Debug, Deserialize, Serialize)]
+#[non_exhaustive]
+pub struct FinalizedProgramInfo {
+    pub base64: String,
+    pub hex: String,
+    pub cmr: String,
+    pub imr: String,
+}
+
+/// Describe the encoding of the given finalized program, including its witness data.
+pub fn finalized_program_info<J: Jet>(
+    program: &RedeemNode<J>,
+) -> Result<FinalizedProgramInfo, Error> {
+    let bytes = encode::encode_bytes(|w| program.encode(w))?;
+
+    Ok(FinalizedProgramInfo {
+        base64: encode::encode_base64(|w| program.encode(w))?,
+        hex: bytes.to_hex(),
+        cmr: program.cmr().to_string(),
+        imr: program.imr().to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_sum_value() {
+        let bit = Final::sum(Final::unit(), Final::unit());
+        assert_eq!(
+            parse_witness_value("00", &bit).unwrap(),
+            Value::sum_l(Value::unit())
+        );
+        assert_eq!(
+            parse_witness_value("80", &bit).unwrap(),
+            Value::sum_r(Value::unit())
+        );
+    }
+
+    #[test]
+    fn parse_product_value() {
+        // Left components come first, so the bits of integers are big-endian
+        let ty = Final::two_two_n(3);
+        assert_eq!(parse_witness_value("a5", &ty).unwrap(), Value::u8(0xa5));
+
+        let ty = Final::product(
+            Final::two_two_n(3),
+            Final::sum(Final::unit(), Final::unit()),
+        );
+        assert_eq!(
+            parse_witness_value("a580", &ty).unwrap(),
+            Value::prod(Value::u8(0xa5), Value::sum_r(Value::unit()))
+        );
+    }
+
+    #[test]
+    fn parse_unit_value() {
+        assert_eq!(
+            parse_witness_value("", &Final::unit()).unwrap(),
+            Value::unit()
+        );
+        assert!(parse_witness_value("00", &Final::unit()).is_err());
+    }
+
+    #[test]
+    fn reject_bad_padding() {
+        let bit = Final::sum(Final::unit(), Final::unit());
+        // Missing bits
+        assert!(parse_witness_value("", &bit).is_err());
+        // Non-zero padding
+        assert!(parse_witness_value("81", &bit).is_err());
+        // Padding of a full byte
+        assert!(parse_witness_value("8000", &bit).is_err());
+    }
+}